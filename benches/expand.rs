@@ -0,0 +1,50 @@
+//! Benchmarks the expansion of a `pallet::Call` with the size of a full
+//! runtime
+//!
+//! Run with `cargo bench`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use substrate_pallet_call_extract::PalletCallConfig;
+
+/// Creates a `pallet::Call` with `calls` variants
+fn runtime_call(calls: usize) -> String {
+    let mut call = String::from("pub enum Call<T: Config> {\n");
+    call.push_str("#[doc(hidden)] #[codec(skip)] __Ignore(PhantomData<(T,)>, Never),\n");
+    for idx in 0..calls {
+        call.push_str(&format!(
+            "/// Call number {idx}\n\
+             #[codec(index = {index})]\n\
+             call_{idx}(<T::Lookup as StaticLookup>::Source, #[codec(compact)] T::Balance, \
+             T::AccountId, T::Hash, Vec<u8>),\n",
+            idx = idx,
+            index = idx
+        ));
+    }
+    call.push('}');
+    call
+}
+
+fn expand(c: &mut Criterion) {
+    // all the pallets of a runtime
+    let content = runtime_call(250);
+    let pallet = PalletCallConfig::default()
+        .frame_support_runtime_debug()
+        .parse(&content)
+        .unwrap();
+
+    c.bench_function("expand runtime", |b| {
+        b.iter(|| black_box(pallet.expand().unwrap()))
+    });
+    c.bench_function("parse and expand runtime", |b| {
+        b.iter(|| {
+            PalletCallConfig::default()
+                .parse(black_box(&content))
+                .unwrap()
+                .expand()
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, expand);
+criterion_main!(benches);
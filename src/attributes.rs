@@ -0,0 +1,177 @@
+//! Helpers for inspecting and rewriting attributes
+
+use crate::CfgPolicy;
+use std::collections::BTreeMap;
+use syn::{Attribute, Lit, LitInt, LitStr, Meta, MetaNameValue, NestedMeta};
+
+/// Returns the index of the call, which is either set explicitly via
+/// `#[codec(index = ..)]` or the position of the call
+pub(crate) fn call_index(attrs: &[Attribute], position: usize) -> syn::Result<u8> {
+    if let Some(index) = explicit_index(attrs) {
+        return index.base10_parse();
+    }
+    if position > u8::MAX as usize {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "Calls exceed the maximum number of 256",
+        ));
+    }
+    Ok(position as u8)
+}
+
+/// Returns the literal of `#[codec(index = ..)]`, if the index is set
+/// explicitly
+pub(crate) fn explicit_index(attrs: &[Attribute]) -> Option<LitInt> {
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+        if let Ok(Meta::List(list)) = attr.parse_meta() {
+            for nested in list.nested.iter() {
+                if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+                    if nv.path.is_ident("index") {
+                        if let Lit::Int(ref index) = nv.lit {
+                            return Some(index.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Removes all `#[doc = ".."]` attributes
+pub(crate) fn remove_doc_attributes(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|attr| !attr.path.is_ident("doc"));
+}
+
+/// Returns the lines of all `#[doc = ".."]` attributes
+pub(crate) fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(doc), ..
+            })) => Some(doc.value()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the deprecation note if a doc line starts with `Deprecated`, like
+/// `DEPRECATED: use transfer_keep_alive instead`
+pub(crate) fn deprecation_note(lines: &[String]) -> Option<String> {
+    const DEPRECATED: &str = "deprecated";
+    lines.iter().find_map(|line| {
+        let line = line.trim();
+        if line.len() >= DEPRECATED.len()
+            && line.is_char_boundary(DEPRECATED.len())
+            && line[..DEPRECATED.len()].eq_ignore_ascii_case(DEPRECATED)
+        {
+            Some(
+                line[DEPRECATED.len()..]
+                    .trim_start_matches(|c: char| c == ':' || c == '.' || c.is_whitespace())
+                    .to_string(),
+            )
+        } else {
+            None
+        }
+    })
+}
+
+/// Creates a `#[doc = ".."]` attribute for every line
+pub(crate) fn doc_attributes(lines: &[String]) -> Vec<Attribute> {
+    lines
+        .iter()
+        .map(|line| syn::parse_quote!(#[doc = #line]))
+        .collect()
+}
+
+/// Whether this is a `#[codec(compact)]` attribute
+pub(crate) fn is_compact(attr: &Attribute) -> bool {
+    is_codec_flag(attr, "compact")
+}
+
+/// Whether this is a `#[codec(skip)]` attribute
+pub(crate) fn is_skip(attr: &Attribute) -> bool {
+    is_codec_flag(attr, "skip")
+}
+
+/// Whether this is a `#[codec(..)]` attribute that contains the flag
+fn is_codec_flag(attr: &Attribute, flag: &str) -> bool {
+    if !attr.path.is_ident("codec") {
+        return false;
+    }
+    match attr.parse_meta() {
+        Ok(Meta::List(list)) => list
+            .nested
+            .iter()
+            .any(|nested| matches!(nested, NestedMeta::Meta(Meta::Path(p)) if p.is_ident(flag))),
+        _ => false,
+    }
+}
+
+/// Whether this is a `#[cfg(..)]` or `#[cfg_attr(..)]` attribute
+fn is_cfg(attr: &Attribute) -> bool {
+    attr.path.is_ident("cfg") || attr.path.is_ident("cfg_attr")
+}
+
+/// Applies the policy to all `#[cfg(..)]` and `#[cfg_attr(..)]` attributes
+pub(crate) fn apply_cfg_policy(attrs: &mut Vec<Attribute>, policy: &CfgPolicy) -> syn::Result<()> {
+    match policy {
+        CfgPolicy::Keep => {}
+        CfgPolicy::Strip => attrs.retain(|attr| !is_cfg(attr)),
+        CfgPolicy::Map(features) => {
+            for attr in attrs.iter_mut().filter(|attr| is_cfg(attr)) {
+                let mut meta = attr.parse_meta()?;
+                rename_features(&mut meta, features);
+                *attr = syn::parse_quote!(#[#meta]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renames all `feature = ".."` predicates
+fn rename_features(meta: &mut Meta, features: &BTreeMap<String, String>) {
+    match meta {
+        Meta::NameValue(nv) if nv.path.is_ident("feature") => {
+            if let Lit::Str(feature) = &nv.lit {
+                if let Some(renamed) = features.get(&feature.value()) {
+                    nv.lit = Lit::Str(LitStr::new(renamed, feature.span()));
+                }
+            }
+        }
+        Meta::List(list) => {
+            for nested in list.nested.iter_mut() {
+                if let NestedMeta::Meta(meta) = nested {
+                    rename_features(meta, features);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_rename_features() {
+        let mut attrs: Vec<Attribute> = vec![
+            syn::parse_quote!(#[cfg(all(feature = "runtime-benchmarks", not(feature = "std")))]),
+            syn::parse_quote!(#[codec(compact)]),
+        ];
+        let features = vec![("runtime-benchmarks".to_string(), "benchmarks".to_string())]
+            .into_iter()
+            .collect();
+        apply_cfg_policy(&mut attrs, &CfgPolicy::Map(features)).unwrap();
+
+        let expected: Attribute =
+            syn::parse_quote!(#[cfg(all(feature = "benchmarks", not(feature = "std")))]);
+        assert_eq!(attrs[0], expected);
+
+        apply_cfg_policy(&mut attrs, &CfgPolicy::Strip).unwrap();
+        assert_eq!(attrs.len(), 1);
+    }
+}
@@ -0,0 +1,878 @@
+//! Expand multiple pallets at once
+
+use crate::{
+    concrete, options, output, parse_ident, transform::generic_type, CallOptions, Error,
+    ExpandedCall, ExpandedVariant, PalletCall, PalletIndex, Result, WriteMode,
+};
+use inflector::Inflector;
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Fields, Ident, Token, Type, TypePath};
+
+/// Names the file of a module
+type FileName = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// A generic that is introduced by one or more pallets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedGeneric {
+    /// The name of the generic, unique across all pallets of the set
+    pub name: String,
+    /// The original type path, like `T :: AccountId`
+    pub ty: String,
+    /// The modules of all the pallets that use this generic
+    pub pallets: Vec<String>,
+}
+
+/// A set of pallet calls that are expanded together
+///
+/// Generics that resolve to the same original type path (like `T::AccountId`)
+/// across multiple pallets are detected and given a single name. Generics
+/// with a concrete type registered via `resolve` are emitted only once in a
+/// shared `types` module that all generated enums reference, instead of being
+/// a generic parameter of each enum.
+///
+/// # Example
+///
+/// ```no_run
+/// # use substrate_pallet_call_extract::{CallSet, PalletCallConfig};
+/// let set = CallSet::default()
+///     .push("balances", PalletCallConfig::default().parse_pallet("pallet-balances").unwrap())
+///     .push("assets", PalletCallConfig::default().parse_pallet("pallet-assets").unwrap())
+///     .resolve("AccountId", syn::parse_str("sp_runtime::AccountId32").unwrap());
+/// let expanded = set.expand().unwrap();
+/// ```
+#[derive(Default)]
+pub struct CallSet {
+    /// All pallets by the name of the module they're emitted in
+    pallets: Vec<(String, PalletCall<'static>)>,
+    /// Concrete types for shared generics
+    types: BTreeMap<String, Type>,
+    /// Names the file of a module when writing to a directory
+    file_name: Option<FileName>,
+    /// Whether to emit a `prelude` module
+    prelude: bool,
+    /// The name of the outer enum and the pallet indices of the runtime
+    outer: Option<(String, Vec<PalletIndex>)>,
+}
+
+/// The expanded items of all pallets of a set
+struct Modules<'a> {
+    /// The resolved generics
+    types: Option<TokenStream>,
+    /// The re-exports of all generated items
+    prelude: Option<TokenStream>,
+    /// The outer enum of all pallet calls
+    outer: Option<TokenStream>,
+    /// The content of the module of every pallet
+    modules: Vec<(&'a str, TokenStream)>,
+}
+
+impl CallSet {
+    /// Add a pallet call that will be emitted in a `pub mod <module>`
+    pub fn push(mut self, module: impl Into<String>, call: PalletCall<'_>) -> Self {
+        self.pallets.push((module.into(), call.into_owned()));
+        self
+    }
+
+    /// Use a concrete type for the shared generic with the given name
+    pub fn resolve(mut self, generic: impl Into<String>, ty: Type) -> Self {
+        self.types.insert(generic.into(), ty);
+        self
+    }
+
+    /// Use this function to name the file of a module when writing to a
+    /// directory, by default the file of `balances` is `balances.rs`
+    pub fn file_name<F>(mut self, name: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.file_name = Some(Arc::new(name));
+        self
+    }
+
+    /// Also emit a `prelude` module that re-exports the generated items of all
+    /// pallets under names prefixed with the pascal cased module name
+    ///
+    /// For the `balances` module, the generated enum is re-exported as
+    /// `BalancesCall`, the companion enum of explicit indices as
+    /// `BalancesCallName`, the concrete enum like `BalancesRuntimeCall` and
+    /// the `types` module of its aliases as `balances_types`. `Config`
+    /// aliases like `BalanceOf` are re-exported as `BalancesBalanceOf` and the
+    /// resolved generics of the set under their own names.
+    pub fn prelude(mut self) -> Self {
+        self.prelude = true;
+        self
+    }
+
+    /// Also emit an outer enum with the given name, like `RuntimeCall`, with a
+    /// variant for every pallet that wraps its generated enum
+    ///
+    /// The variants are named and indexed like the pallets of the runtime,
+    /// see [`construct_runtime_pallets`](crate::construct_runtime_pallets),
+    /// so the outer enum encodes calls byte for byte like the runtime's
+    /// `RuntimeCall`. A module matches the pallet with its pascal cased name,
+    /// `balances` matches `Balances`. The enum has the derives that all pallet
+    /// calls have, and the visibility and codec crate of the first pallet.
+    pub fn outer_enum<I>(mut self, name: impl Into<String>, pallets: I) -> Self
+    where
+        I: IntoIterator<Item = PalletIndex>,
+    {
+        self.outer = Some((name.into(), pallets.into_iter().collect()));
+        self
+    }
+
+    /// Returns all generics introduced by the pallets of this set
+    ///
+    /// The generics are unique by their original type path. If two different
+    /// type paths would result in the same generic name, the name of the
+    /// latter is prefixed with the pascal cased module name of the pallet
+    /// that introduced it.
+    pub fn shared_generics(&self) -> Result<Vec<SharedGeneric>> {
+        let calls = self.expand_calls()?;
+        Ok(self.shared(&calls))
+    }
+
+    /// Expands all pallet calls
+    ///
+    /// Every enum is emitted in its own module, resolved generics are emitted
+    /// in a `types` module.
+    pub fn expand(&self) -> Result<TokenStream> {
+        let Modules {
+            types,
+            prelude,
+            outer,
+            modules,
+        } = self.expand_modules()?;
+        let prelude = match prelude {
+            Some(prelude) => quote! {
+                pub mod prelude {
+                    #prelude
+                }
+            },
+            None => quote! {},
+        };
+        let types = match types {
+            Some(types) => quote! {
+                pub mod types {
+                    #types
+                }
+            },
+            None => quote! {},
+        };
+        let modules = modules
+            .into_iter()
+            .map(|(module, tokens)| {
+                let module = options::parse_config("module", module, syn::parse_str::<Ident>)?;
+                Ok(quote! {
+                    pub mod #module {
+                        #tokens
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            #types
+            #( #modules )*
+            #outer
+            #prelude
+        })
+    }
+
+    /// Expands all pallet calls into one file per module and a `mod.rs` that
+    /// declares them, by file name
+    ///
+    /// Resolved generics are emitted in a `types.rs`. Files are named by the
+    /// `file_name` function, modules with a different file name are declared
+    /// with a `#[path]` attribute.
+    pub fn expand_files(&self) -> Result<Vec<(String, TokenStream)>> {
+        let Modules {
+            types,
+            prelude,
+            outer,
+            modules,
+        } = self.expand_modules()?;
+        let mut files = Vec::with_capacity(modules.len() + 3);
+        let mut declarations = Vec::with_capacity(modules.len() + 1);
+        if let Some(types) = types {
+            declarations.push(quote! { pub mod types; });
+            files.push(("types.rs".to_string(), types));
+        }
+        for (module, tokens) in modules {
+            let file = match self.file_name.as_ref() {
+                Some(file_name) => (file_name)(module),
+                None => format!("{}.rs", module),
+            };
+            let ident = options::parse_config("module", module, syn::parse_str::<Ident>)?;
+            if file == format!("{}.rs", module) {
+                declarations.push(quote! { pub mod #ident; });
+            } else {
+                declarations.push(quote! {
+                    #[path = #file]
+                    pub mod #ident;
+                });
+            }
+            files.push((file, tokens));
+        }
+        if let Some(prelude) = prelude {
+            declarations.push(quote! { pub mod prelude; });
+            files.push(("prelude.rs".to_string(), prelude));
+        }
+        if files.iter().any(|(file, _)| file == "mod.rs") {
+            return Err(Error::Config(
+                "a module can't be written to `mod.rs`".to_string(),
+            ));
+        }
+        files.push((
+            "mod.rs".to_string(),
+            quote! {
+                #( #declarations )*
+                #outer
+            },
+        ));
+        Ok(files)
+    }
+
+    /// Expands all pallet calls and writes every module to its own file in
+    /// the directory, see [`CallSet::expand_files`]
+    ///
+    /// Returns `true` if any file was written.
+    pub fn write_to_dir(&self, dir: impl AsRef<Path>, mode: WriteMode) -> anyhow::Result<bool> {
+        let dir = dir.as_ref();
+        if mode != WriteMode::Check {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut written = false;
+        for (file, tokens) in self.expand_files()? {
+            let content = output::finish(output::render(tokens)?, self.options())?;
+            written |= output::write(&dir.join(file), &content, mode)?;
+        }
+        Ok(written)
+    }
+
+    /// Expands all pallet calls, the resolved generics and the content of
+    /// the module of every pallet
+    fn expand_modules(&self) -> Result<Modules<'_>> {
+        let mut calls = self.expand_calls()?;
+        let shared = self.shared(&calls);
+
+        let mut aliases = Vec::new();
+        for generic in &shared {
+            if let Some(ty) = self.types.get(&generic.name) {
+                let ident = parse_ident(&generic.name)?;
+                aliases.push(quote! {
+                    pub type #ident = #ty;
+                });
+            }
+        }
+
+        let mut modules = Vec::with_capacity(calls.len());
+        let mut prelude = Vec::new();
+        if !aliases.is_empty() {
+            prelude.push(quote! { pub use super::types::*; });
+        }
+        for ((module, pallet), call) in self.pallets.iter().zip(calls.iter_mut()) {
+            let mut renamed = Vec::new();
+            for (ty, name) in call.generics.iter_mut() {
+                let key = type_key(ty);
+                let generic = shared
+                    .iter()
+                    .find(|g| type_key(&g.ty) == key)
+                    .ok_or_else(|| {
+                        Error::Config(format!(
+                            "generic `{}` of `{}` is not shared",
+                            ty.replace(' ', ""),
+                            module
+                        ))
+                    })?;
+                if generic.name != *name {
+                    let replacement = generic_type(parse_ident(&generic.name)?);
+                    renamed.push((std::mem::replace(name, generic.name.clone()), replacement));
+                }
+            }
+            // all renames at once, so swapped names are not replaced twice
+            let renamed = renamed
+                .iter()
+                .map(|(name, ty)| (name.as_str(), ty.clone()))
+                .collect();
+            replace_generics(&mut call.variants, &renamed)?;
+
+            let types = &self.types;
+            let mut resolved = Vec::new();
+            call.generics.retain(|_, name| {
+                if types.contains_key(name.as_str()) {
+                    resolved.push(name.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            let mut replacements = BTreeMap::new();
+            for name in &resolved {
+                let ident = parse_ident(name)?;
+                replacements.insert(name.as_str(), syn::parse_quote!(super::types::#ident));
+            }
+            replace_generics(&mut call.variants, &replacements)?;
+
+            let call_enum = pallet.emit_enum(call)?;
+            modules.push((module.as_str(), pallet.wrap(None, call_enum)?));
+            if self.prelude {
+                prelude.extend(reexports(module, pallet, call)?);
+            }
+        }
+
+        let outer = match self.outer.as_ref() {
+            Some((name, pallets)) => Some(self.emit_outer(name, pallets, &calls)?),
+            None => None,
+        };
+        let types = if aliases.is_empty() {
+            None
+        } else {
+            Some(quote! { #( #aliases )* })
+        };
+        let prelude = if self.prelude {
+            Some(quote! { #( #prelude )* })
+        } else {
+            None
+        };
+        Ok(Modules {
+            types,
+            prelude,
+            outer,
+            modules,
+        })
+    }
+
+    /// Emits the outer enum with a variant for the call of every pallet,
+    /// indexed like the pallet in the runtime
+    fn emit_outer(
+        &self,
+        name: &str,
+        pallets: &[PalletIndex],
+        calls: &[ExpandedCall],
+    ) -> Result<TokenStream> {
+        let name = options::parse_config("outer enum", name, syn::parse_str::<Ident>)?;
+        let mut lifetimes = Vec::new();
+        let mut generics = Vec::new();
+        let mut variants = Vec::with_capacity(calls.len());
+        for ((module, pallet), call) in self.pallets.iter().zip(calls) {
+            let options = pallet.config.options();
+            if options.keep_generics || options.without_codec {
+                return Err(Error::Config(format!(
+                    "the outer enum requires erased generics and the codec derives of `{}`",
+                    module
+                )));
+            }
+            let runtime_pallet = pallets
+                .iter()
+                .find(|p| p.name == *module || p.name == module.to_pascal_case())
+                .ok_or_else(|| {
+                    Error::Config(format!("no pallet of the runtime matches `{}`", module))
+                })?;
+            let variant = parse_ident(&runtime_pallet.name)?;
+            let index = Literal::u8_unsuffixed(runtime_pallet.index);
+            let module = options::parse_config("module", module, syn::parse_str::<Ident>)?;
+            let call_name = &call.name;
+            let params = call
+                .generics
+                .values()
+                .map(|generic| parse_ident(generic))
+                .collect::<syn::Result<Vec<_>>>()?;
+            let call_lifetimes = &call.lifetimes;
+            let ty = if params.is_empty() && call_lifetimes.is_empty() {
+                quote! { #module::#call_name }
+            } else {
+                quote! { #module::#call_name<#( #call_lifetimes, )* #( #params ),*> }
+            };
+            for lifetime in call_lifetimes {
+                if !lifetimes.contains(lifetime) {
+                    lifetimes.push(lifetime.clone());
+                }
+            }
+            for param in params {
+                if !generics.contains(&param) {
+                    generics.push(param);
+                }
+            }
+            variants.push(quote! {
+                #[codec(index = #index)]
+                #variant(#ty)
+            });
+        }
+        // only the derives that all pallet calls implement
+        let mut derives = match calls.first() {
+            Some(call) => parse_derives(&call.attrs)?,
+            None => Vec::new(),
+        };
+        for call in calls.iter().skip(1) {
+            let other = parse_derives(&call.attrs)?
+                .iter()
+                .map(|derive| quote!(#derive).to_string())
+                .collect::<BTreeSet<_>>();
+            derives.retain(|derive| other.contains(&quote!(#derive).to_string()));
+        }
+        let derive = if derives.is_empty() {
+            quote! {}
+        } else {
+            quote! { #[derive(#( #derives, )*)] }
+        };
+        let (vis, codec_crate) = match self.pallets.first() {
+            Some((_, pallet)) => {
+                let options = pallet.config.options();
+                // the derives find the configured codec crate with this
+                let codec_crate = if options.codec_crate.is_some() {
+                    let codec_crate = options.parse_codec_crate()?;
+                    quote! { #[codec(crate = #codec_crate)] }
+                } else {
+                    quote! {}
+                };
+                (pallet.visibility()?, codec_crate)
+            }
+            None => (syn::parse_quote!(pub), quote! {}),
+        };
+        let params = if generics.is_empty() && lifetimes.is_empty() {
+            quote! {}
+        } else {
+            quote! { <#( #lifetimes, )* #( #generics ),*> }
+        };
+        Ok(quote! {
+            /// The calls of all pallets, encoded like the `RuntimeCall` of the
+            /// runtime
+            #derive
+            #codec_crate
+            #vis enum #name #params {
+                #( #variants ),*
+            }
+        })
+    }
+
+    /// Expands all pallet calls and writes the generated code to the given
+    /// path
+    ///
+    /// Returns `true` if the file was written.
+    pub fn write_to_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        mode: WriteMode,
+    ) -> anyhow::Result<bool> {
+        let content = self.to_source()?;
+        Ok(output::write(path.as_ref(), &content, mode)?)
+    }
+
+    /// Expands all pallet calls and renders them as source code with the
+    /// preamble and formatting of the first pallet
+    pub fn to_source(&self) -> Result<String> {
+        output::finish(output::render(self.expand()?)?, self.options())
+    }
+
+    /// The options of the first pallet, the files of the set share its
+    /// preamble and formatting
+    fn options(&self) -> Option<&CallOptions> {
+        self.pallets.first().map(|(_, call)| call.config.options())
+    }
+
+    /// Expands all pallet calls, fails with the errors of all pallets
+    fn expand_calls(&self) -> Result<Vec<ExpandedCall>> {
+        let mut calls = Vec::with_capacity(self.pallets.len());
+        let mut errors = Vec::new();
+        for (_, call) in &self.pallets {
+            match call.expand_call() {
+                Ok(call) => calls.push(call),
+                Err(err) => errors.push(err),
+            }
+        }
+        Error::combine(errors)?;
+        Ok(calls)
+    }
+
+    /// Deduplicates the generics of all calls by their original type path
+    fn shared(&self, calls: &[ExpandedCall]) -> Vec<SharedGeneric> {
+        let mut shared: Vec<SharedGeneric> = Vec::new();
+        for ((module, _), call) in self.pallets.iter().zip(calls) {
+            for (ty, name) in &call.generics {
+                let key = type_key(ty);
+                if let Some(generic) = shared.iter_mut().find(|g| type_key(&g.ty) == key) {
+                    generic.pallets.push(module.clone());
+                    continue;
+                }
+                let name = if shared.iter().any(|g| &g.name == name) {
+                    format!("{}{}", module.to_pascal_case(), name)
+                } else {
+                    name.clone()
+                };
+                shared.push(SharedGeneric {
+                    name,
+                    ty: ty.clone(),
+                    pallets: vec![module.clone()],
+                });
+            }
+        }
+        shared
+    }
+}
+
+/// The type path of a generic to compare it across pallets, a qualified
+/// path on a generic like `<T as Config>::AccountId` is the same as
+/// `T::AccountId`
+fn type_key(ty: &str) -> String {
+    let mut path = match syn::parse_str::<TypePath>(ty) {
+        Ok(path) => path,
+        Err(_) => return ty.to_string(),
+    };
+    if let Some(qself) = path.qself.take() {
+        match &*qself.ty {
+            Type::Path(generic)
+                if generic.qself.is_none() && generic.path.get_ident().is_some() =>
+            {
+                let segments = path.path.segments.into_iter().skip(qself.position);
+                path.path = syn::Path {
+                    leading_colon: None,
+                    segments: std::iter::once(generic.path.segments[0].clone())
+                        .chain(segments)
+                        .collect(),
+                };
+            }
+            _ => path.qself = Some(qself),
+        }
+    }
+    quote!(#path).to_string()
+}
+
+/// The `prelude` re-exports of the generated items of a pallet
+fn reexports(module: &str, pallet: &PalletCall, call: &ExpandedCall) -> Result<Vec<TokenStream>> {
+    let options = pallet.config.options();
+    let vis = pallet.visibility()?;
+    let prefix = module.to_pascal_case();
+    let module = options::parse_config("module", module, syn::parse_str::<Ident>)?;
+    let reexport = |item: &str, name: String| -> Result<TokenStream> {
+        let item = parse_ident(item)?;
+        let name = parse_ident(&name)?;
+        Ok(quote! { #vis use super::#module::#item as #name; })
+    };
+
+    let name = call.name.to_string();
+    let mut reexports = vec![reexport(&name, format!("{}Call", prefix))?];
+    if options.explicit_indices {
+        reexports.push(reexport(
+            &format!("{}Name", name),
+            format!("{}CallName", prefix),
+        )?);
+    }
+    if let Some(concrete) = options.concrete.as_ref() {
+        reexports.push(reexport(
+            &concrete.name,
+            format!("{}{}", prefix, concrete.name),
+        )?);
+    }
+    if options.keep_generics {
+        for generic in call.generics.values() {
+            let alias = format!("{}Of", generic);
+            reexports.push(reexport(&alias, format!("{}{}", prefix, alias))?);
+        }
+    } else if !options.aliases.is_empty() {
+        reexports.push(reexport("types", format!("{}_types", module))?);
+    }
+    Ok(reexports)
+}
+
+/// Replaces all fields that have the given generic as type
+fn replace_generics(
+    variants: &mut [ExpandedVariant],
+    replacements: &BTreeMap<&str, Type>,
+) -> Result<()> {
+    if replacements.is_empty() {
+        return Ok(());
+    }
+    for variant in variants {
+        let fields = match &mut variant.variant.fields {
+            Fields::Named(fields) => &mut fields.named,
+            Fields::Unnamed(fields) => &mut fields.unnamed,
+            Fields::Unit => continue,
+        };
+        for field in fields {
+            // also nested uses like `codec::Compact<Balance>` or `&'a [Hash]`
+            let ty = &field.ty;
+            field.ty = syn::parse2(concrete::substitute(quote!(#ty), replacements))?;
+        }
+    }
+    Ok(())
+}
+
+/// The derives of the attributes, like `Clone` of `#[derive(Clone)]`
+fn parse_derives(attrs: &[Attribute]) -> Result<Vec<syn::Path>> {
+    let mut derives = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("derive")) {
+        let paths = attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)?;
+        derives.extend(paths);
+    }
+    Ok(derives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompactMode, PalletCallConfig};
+
+    #[test]
+    fn can_share_generics() {
+        let balances = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+            }
+        "#,
+            )
+            .unwrap();
+        let assets = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(<T as Config>::AccountId, T::Currency::Balance),
+            }
+        "#,
+            )
+            .unwrap();
+
+        let set = CallSet::default()
+            .push("balances", balances)
+            .push("assets", assets)
+            .resolve("AccountId", syn::parse_str("u64").unwrap());
+
+        let shared = set.shared_generics().unwrap();
+        assert_eq!(shared.len(), 3);
+        assert_eq!(shared[0].name, "AccountId");
+        assert_eq!(shared[0].pallets, vec!["balances", "assets"]);
+        assert_eq!(shared[2].name, "AssetsBalance");
+
+        let expected = quote! {
+            pub mod types {
+                pub type AccountId = u64;
+            }
+            pub mod balances {
+                #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+                pub enum Call<Balance> {
+                    Transfer(super::types::AccountId, #[codec(compact)] Balance)
+                }
+            }
+            pub mod assets {
+                #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+                pub enum Call<AssetsBalance> {
+                    Transfer(super::types::AccountId, AssetsBalance)
+                }
+            }
+        };
+        assert_eq!(set.expand().unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_resolve_nested_generics() {
+        let call = PalletCallConfig::default()
+            .compact_mode(CompactMode::Wrap)
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+            }
+        "#,
+            )
+            .unwrap();
+        let set = CallSet::default()
+            .push("balances", call)
+            .resolve("Balance", syn::parse_str("u128").unwrap());
+
+        let expected = quote! {
+            pub mod types {
+                pub type Balance = u128;
+            }
+            pub mod balances {
+                #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+                pub enum Call<AccountId> {
+                    Transfer(AccountId, codec::Compact<super::types::Balance>)
+                }
+            }
+        };
+        assert_eq!(set.expand().unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_split_files() {
+        let call = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+            }
+        "#,
+            )
+            .unwrap();
+        let set = CallSet::default()
+            .push("balances", call.clone())
+            .push("assets", call)
+            .resolve("AccountId", syn::parse_str("u64").unwrap())
+            .file_name(|module| match module {
+                "assets" => "assets_call.rs".to_string(),
+                module => format!("{}.rs", module),
+            });
+
+        let files = set.expand_files().unwrap();
+        let names = files
+            .iter()
+            .map(|(file, _)| file.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["types.rs", "balances.rs", "assets_call.rs", "mod.rs"]
+        );
+        let expected = quote! {
+            pub mod types;
+            pub mod balances;
+            #[path = "assets_call.rs"]
+            pub mod assets;
+        };
+        assert_eq!(files[3].1.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_outer_enum() {
+        let call = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+            }
+        "#,
+            )
+            .unwrap();
+        let set = CallSet::default()
+            .push("balances", call.clone())
+            .push("assets", call)
+            .resolve("AccountId", syn::parse_str("u64").unwrap())
+            .outer_enum(
+                "RuntimeCall",
+                vec![
+                    PalletIndex {
+                        name: "Assets".to_string(),
+                        index: 50,
+                    },
+                    PalletIndex {
+                        name: "Balances".to_string(),
+                        index: 5,
+                    },
+                ],
+            );
+
+        let expected = quote! {
+            /// The calls of all pallets, encoded like the `RuntimeCall` of the
+            /// runtime
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum RuntimeCall<Balance> {
+                #[codec(index = 5)]
+                Balances(balances::Call<Balance>),
+                #[codec(index = 50)]
+                Assets(assets::Call<Balance>)
+            }
+        };
+        assert!(set
+            .expand()
+            .unwrap()
+            .to_string()
+            .ends_with(&expected.to_string()));
+    }
+
+    #[test]
+    fn can_emit_outer_enum_with_common_derives() {
+        const CALL: &str = r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+            }
+        "#;
+        let balances = PalletCallConfig::default()
+            .visibility("pub(crate)")
+            .codec_crate("::parity_scale_codec")
+            .push_derive(syn::parse_quote!(Debug))
+            .parse(CALL)
+            .unwrap();
+        let assets = PalletCallConfig::default()
+            .visibility("pub(crate)")
+            .codec_crate("::parity_scale_codec")
+            .parse(CALL)
+            .unwrap();
+        let set = CallSet::default()
+            .push("balances", balances)
+            .push("assets", assets)
+            .resolve("AccountId", syn::parse_str("u64").unwrap())
+            .outer_enum(
+                "RuntimeCall",
+                vec![
+                    PalletIndex {
+                        name: "Assets".to_string(),
+                        index: 50,
+                    },
+                    PalletIndex {
+                        name: "Balances".to_string(),
+                        index: 5,
+                    },
+                ],
+            );
+
+        let expected = quote! {
+            /// The calls of all pallets, encoded like the `RuntimeCall` of the
+            /// runtime
+            #[derive(
+                Clone,
+                PartialEq,
+                Eq,
+                ::parity_scale_codec::Encode,
+                ::parity_scale_codec::Decode,
+            )]
+            #[codec(crate = ::parity_scale_codec)]
+            pub(crate) enum RuntimeCall<Balance> {
+                #[codec(index = 5)]
+                Balances(balances::Call<Balance>),
+                #[codec(index = 50)]
+                Assets(assets::Call<Balance>)
+            }
+        };
+        assert!(set
+            .expand()
+            .unwrap()
+            .to_string()
+            .ends_with(&expected.to_string()));
+    }
+
+    #[test]
+    fn can_emit_prelude() {
+        let call = PalletCallConfig::default()
+            .explicit_indices()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+            }
+        "#,
+            )
+            .unwrap();
+        let set = CallSet::default()
+            .push("balances", call)
+            .resolve("AccountId", syn::parse_str("u64").unwrap())
+            .prelude();
+
+        let expected = quote! {
+            pub mod prelude {
+                pub use super::types::*;
+                pub use super::balances::Call as BalancesCall;
+                pub use super::balances::CallName as BalancesCallName;
+            }
+        };
+        assert!(set
+            .expand()
+            .unwrap()
+            .to_string()
+            .ends_with(&expected.to_string()));
+    }
+}
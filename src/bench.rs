@@ -0,0 +1,60 @@
+//! Emits a criterion benchmark of encoding and decoding every call
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Path;
+
+/// Emits the content of a `benches/` file that benchmarks encoding and
+/// decoding the examples of every call with criterion
+///
+/// The `concrete` path is the path of the concrete enum in the benchmark,
+/// like `my_indexer::balances::RuntimeCall`, which requires its `examples`.
+pub(crate) fn emit(concrete: &Path) -> TokenStream {
+    quote! {
+        use criterion::{black_box, criterion_group, criterion_main, Criterion};
+        use #concrete as Call;
+
+        fn encode(c: &mut Criterion) {
+            let mut group = c.benchmark_group("encode");
+            for (name, call) in Call::examples() {
+                group.bench_function(name, |b| {
+                    b.iter(|| codec::Encode::encode(black_box(&call)))
+                });
+            }
+            group.finish();
+        }
+
+        fn decode(c: &mut Criterion) {
+            let mut group = c.benchmark_group("decode");
+            for (name, encoded) in Call::encoded_examples() {
+                group.bench_function(name, |b| {
+                    b.iter(|| {
+                        <Call as codec::Decode>::decode(&mut black_box(encoded.as_slice()))
+                            .unwrap()
+                    })
+                });
+            }
+            group.finish();
+        }
+
+        criterion_group!(benches, encode, decode);
+        criterion_main!(benches);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_emit_bench() {
+        let bench = emit(&syn::parse_quote!(my_indexer::balances::RuntimeCall)).to_string();
+        assert!(bench.contains(
+            &quote!(
+                use my_indexer::balances::RuntimeCall as Call;
+            )
+            .to_string()
+        ));
+        assert!(bench.ends_with(&quote!(criterion_main!(benches);).to_string()));
+    }
+}
@@ -0,0 +1,461 @@
+//! Command line interface to extract a generic `Call` enum
+//!
+//! Reads the expanded pallet from a file or stdin and writes the generated
+//! code to a file or stdout, so it can be used in pipelines:
+//!
+//! ```sh
+//! cargo expand -p pallet-balances | pallet-call-extract - --pallet balances > balances.rs
+//! ```
+
+use anyhow::Context;
+use std::ffi::OsStr;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use substrate_pallet_call_extract::{
+    construct_runtime_pallets, diff, expand_crate, pallet_enum_source, resolve_package,
+    scan_workspace, Cache, CallAbi, Compatibility, PalletCall, PalletCallConfig,
+};
+
+const USAGE: &str = "\
+Usage: pallet-call-extract <INPUT> [OPTIONS]
+       pallet-call-extract --package <NAME> [OPTIONS]
+       pallet-call-extract --scan <DIR>
+       pallet-call-extract diff --base <REV> [--head <REV>] (<INPUT> | --package <NAME>)
+
+Arguments:
+  <INPUT>              File with the expanded pallet or its `Call` enum, `-` for stdin
+
+Options:
+  --pallet <NAME>      Emit the enum in a `pub mod <NAME>`
+  --config <PATH>      Load the options from a `.toml` or `.json` file
+  --type-path <FROM=TO>
+                       Replace the type path FROM with TO in all field types
+  --preamble <PATH>    Prepend the content of this file, like a license header,
+                       to the generated code
+  --rustfmt <EDITION>  Format the generated code with the system `rustfmt` and
+                       this edition, a `rustfmt.toml` is set in the config
+  --output <PATH>      Write to this file instead of stdout, `-` for stdout
+  --json               Emit the call metadata as JSON instead of Rust
+  --abi                Emit the call ABI as JSON instead of Rust, with
+                       `--check` this fails if the committed ABI at --output
+                       differs
+  --typescript         Emit TypeScript definitions instead of Rust, this
+                       requires a substitution for every generic
+  --bench <MODULE>     Emit a criterion benchmark of the concrete enum in
+                       MODULE instead of Rust, this requires encoded examples
+  --compile-test <INCLUDE>
+                       Emit a compile-pass test that includes the generated
+                       file at INCLUDE instead of Rust
+  --verify             Run `cargo check` on the generated code before it's
+                       written, requires the `verify` feature
+  --check              Don't write the output, fail if the file at --output
+                       is not up to date
+  --source-map <PATH>  Also write the locations of the generated variants in
+                       INPUT to this JSON file
+  --pallet-enum <NAME> Read the `construct_runtime!` of the runtime source
+                       INPUT and emit an enum NAME of its pallets with their
+                       index instead of Rust
+  --cache <DIR>        Cache the generated output in this directory
+  --diff <OLD>         Compare the calls of the previous version OLD with
+                       INPUT, print the changes and exit with the
+                       compatibility of the changes:
+                         0 unchanged, 2 compatible, 3 additive, 4 breaking
+  --package <NAME>     Expand the package, resolved with `cargo metadata` from
+                       the workspace of the current directory, instead of
+                       reading an input
+  --scan <DIR>         Find all pallet crates of the workspace at DIR and
+                       print a manifest that extracts them
+  -h, --help           Print this help
+
+Diff:
+  Compares the calls at two git revisions like `--diff`, the INPUT is read
+  with `git show` and the package is expanded in a worktree of the revision
+  --base <REV>         The previous revision, like a release tag
+  --head <REV>         The new revision, defaults to `HEAD`";
+
+/// The parsed command line arguments
+#[derive(Debug, Default)]
+struct Args {
+    input: String,
+    pallet: Option<String>,
+    config: Option<PathBuf>,
+    type_paths: Vec<(String, String)>,
+    preamble: Option<PathBuf>,
+    rustfmt: Option<String>,
+    output: Option<String>,
+    json: bool,
+    abi: bool,
+    typescript: bool,
+    bench: Option<String>,
+    compile_test: Option<String>,
+    pallet_enum: Option<String>,
+    cache: Option<PathBuf>,
+    diff: Option<String>,
+    check: bool,
+    verify: bool,
+    source_map: Option<PathBuf>,
+    scan: Option<PathBuf>,
+    package: Option<String>,
+    base: Option<String>,
+    head: Option<String>,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Option<Self>> {
+        let mut parsed = Args::default();
+        let mut input = None;
+        let mut args = args.into_iter().peekable();
+        let refs = args.peek().map(|arg| arg == "diff").unwrap_or_default();
+        if refs {
+            args.next();
+        }
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .with_context(|| format!("Missing value for `{}`", arg))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--pallet" => parsed.pallet = Some(value()?),
+                "--config" => parsed.config = Some(value()?.into()),
+                "--type-path" => {
+                    let value = value()?;
+                    let (from, to) = value
+                        .split_once('=')
+                        .with_context(|| format!("Expected `FROM=TO`, got `{}`", value))?;
+                    parsed.type_paths.push((from.to_string(), to.to_string()));
+                }
+                "--preamble" => parsed.preamble = Some(value()?.into()),
+                "--rustfmt" => parsed.rustfmt = Some(value()?),
+                "--output" => parsed.output = Some(value()?),
+                "--bench" => parsed.bench = Some(value()?),
+                "--compile-test" => parsed.compile_test = Some(value()?),
+                "--pallet-enum" => parsed.pallet_enum = Some(value()?),
+                "--cache" => parsed.cache = Some(value()?.into()),
+                "--diff" => parsed.diff = Some(value()?),
+                "--json" => parsed.json = true,
+                "--abi" => parsed.abi = true,
+                "--check" => parsed.check = true,
+                "--verify" => parsed.verify = true,
+                "--source-map" => parsed.source_map = Some(value()?.into()),
+                "--scan" => parsed.scan = Some(value()?.into()),
+                "--package" => parsed.package = Some(value()?),
+                "--base" if refs => parsed.base = Some(value()?),
+                "--head" if refs => parsed.head = Some(value()?),
+                "--typescript" => parsed.typescript = true,
+                flag if flag.starts_with("--") => anyhow::bail!("Unknown option `{}`", flag),
+                _ if input.is_none() => input = Some(arg.clone()),
+                _ => anyhow::bail!("Unexpected argument `{}`", arg),
+            }
+        }
+        if refs {
+            parsed.base = Some(parsed.base.take().context("`diff` requires `--base`")?);
+            parsed.head.get_or_insert_with(|| "HEAD".to_string());
+            if input.is_none() && parsed.package.is_none() {
+                anyhow::bail!("`diff` requires an <INPUT> or `--package`");
+            }
+        }
+        parsed.input = match input {
+            Some(input) => input,
+            // the workspace is scanned or the package expanded instead of
+            // reading an input
+            None if parsed.scan.is_some() || parsed.package.is_some() => String::new(),
+            None => anyhow::bail!("Missing <INPUT>"),
+        };
+        Ok(Some(parsed))
+    }
+}
+
+fn main() {
+    match run() {
+        Ok(code) => std::process::exit(code),
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            eprintln!("\n{}", USAGE);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// The exit code of `--diff` for the compatibility of the changes
+fn exit_code(compatibility: Compatibility) -> i32 {
+    match compatibility {
+        Compatibility::Unchanged => 0,
+        Compatibility::Compatible => 2,
+        Compatibility::Additive => 3,
+        Compatibility::Breaking => 4,
+    }
+}
+
+/// Lists the calls that were added, removed or changed between the
+/// committed and the current ABI
+fn abi_changes(committed: &str, current: &str) -> String {
+    let committed = match serde_json::from_str::<CallAbi>(committed) {
+        Ok(abi) => abi,
+        Err(_) => return "  the committed ABI is missing or invalid".to_string(),
+    };
+    let current = serde_json::from_str::<CallAbi>(current).expect("generated ABI is valid");
+    let mut changes = Vec::new();
+    for call in &committed.calls {
+        match current.calls.iter().find(|other| other.name == call.name) {
+            None => changes.push(format!("  - {}", call.name)),
+            Some(other) if other != call => changes.push(format!("  ~ {}", call.name)),
+            Some(_) => {}
+        }
+    }
+    for call in &current.calls {
+        if !committed.calls.iter().any(|other| other.name == call.name) {
+            changes.push(format!("  + {}", call.name));
+        }
+    }
+    if committed.generics != current.generics {
+        changes.push("  ~ generics".to_string());
+    }
+    changes.join("\n")
+}
+
+/// Checks that the generated code compiles
+#[cfg(feature = "verify")]
+fn verify(call: &PalletCall) -> anyhow::Result<()> {
+    call.verify()
+}
+
+#[cfg(not(feature = "verify"))]
+fn verify(_: &PalletCall) -> anyhow::Result<()> {
+    anyhow::bail!("`--verify` requires the `verify` feature")
+}
+
+/// Reads the expanded pallet at the git revision, either the INPUT file or
+/// the package expanded in a temporary worktree of the revision
+fn source_at(rev: &str, args: &Args) -> anyhow::Result<String> {
+    let package = match args.package.as_deref() {
+        Some(package) => package,
+        None => {
+            return git(&[
+                "show".as_ref(),
+                format!("{}:./{}", rev, args.input).as_ref(),
+            ])
+        }
+    };
+    let worktree = std::env::temp_dir().join(format!(
+        "pallet-call-extract-{}",
+        rev.replace(|c: char| !c.is_ascii_alphanumeric(), "-")
+    ));
+    let prefix = git(&["rev-parse".as_ref(), "--show-prefix".as_ref()])?;
+    git(&[
+        "worktree".as_ref(),
+        "add".as_ref(),
+        "--detach".as_ref(),
+        worktree.as_os_str(),
+        rev.as_ref(),
+    ])?;
+    let manifest_path = worktree.join(prefix.trim()).join("Cargo.toml");
+    let source =
+        resolve_package(package, Some(&manifest_path)).and_then(|krate| expand_crate(&krate));
+    git(&[
+        "worktree".as_ref(),
+        "remove".as_ref(),
+        "--force".as_ref(),
+        worktree.as_os_str(),
+    ])?;
+    source
+}
+
+/// Runs git and returns its stdout
+fn git(args: &[&OsStr]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("Failed to run `git`")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`git {}` failed:\n{}",
+            args.iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Reads the input from the file, or stdin for `-`
+fn read_input(input: &str) -> anyhow::Result<String> {
+    if input == "-" {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .context("Failed to read stdin")?;
+        Ok(content)
+    } else {
+        std::fs::read_to_string(input).with_context(|| format!("Failed to read input {}", input))
+    }
+}
+
+/// Runs the command and returns the exit code
+fn run() -> anyhow::Result<i32> {
+    let args = match Args::parse(std::env::args().skip(1))? {
+        Some(args) => args,
+        None => {
+            println!("{}", USAGE);
+            return Ok(0);
+        }
+    };
+
+    if let Some(root) = args.scan.as_ref() {
+        let crates = scan_workspace(root)?;
+        if crates.is_empty() {
+            anyhow::bail!("No pallet crates found in {}", root.display());
+        }
+        for krate in crates {
+            println!("# {}", krate.source.display());
+            println!("[[pallet]]\ncrate = \"{}\"\n", krate.name);
+        }
+        return Ok(0);
+    }
+
+    let content = match (args.head.as_deref(), args.package.as_deref()) {
+        (Some(head), _) => source_at(head, &args)?,
+        (None, Some(package)) => expand_crate(&resolve_package(package, None)?)?,
+        (None, None) => read_input(&args.input)?,
+    };
+
+    let mut config = match args.config.as_ref() {
+        Some(path) => PalletCallConfig::from_path(path)?,
+        None => PalletCallConfig::default(),
+    };
+    if let Some(pallet) = args.pallet.as_ref() {
+        config = config.module(pallet.as_str());
+    }
+    config
+        .options_mut()
+        .type_paths
+        .extend(args.type_paths.iter().cloned());
+    if let Some(path) = args.preamble.as_ref() {
+        let preamble = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read preamble {}", path.display()))?;
+        config.options_mut().preamble = Some(preamble);
+    }
+    if let Some(edition) = args.rustfmt.as_ref() {
+        config
+            .options_mut()
+            .rustfmt
+            .get_or_insert_with(Default::default)
+            .edition = Some(edition.clone());
+    }
+
+    let old = match args.base.as_deref() {
+        Some(base) => Some(source_at(base, &args)?),
+        None => args.diff.as_deref().map(read_input).transpose()?,
+    };
+    if let Some(old) = old {
+        let old = config.clone().parse_expanded(&old)?;
+        let new = config.clone().parse_expanded(&content)?;
+        let diff = diff(&old, &new)?;
+        let compatibility = diff.compatibility();
+        print!("{}", diff);
+        println!("compatibility: {}", compatibility);
+        return Ok(exit_code(compatibility));
+    }
+
+    if let Some(path) = args.source_map.as_ref() {
+        let file = Some(args.input.clone()).filter(|input| !input.is_empty() && input != "-");
+        let map = config
+            .clone()
+            .parse_expanded(&content)?
+            .to_source_map_json(file)?;
+        std::fs::write(path, map)
+            .with_context(|| format!("Failed to write source map {}", path.display()))?;
+    }
+
+    let generate = || -> anyhow::Result<String> {
+        if let Some(name) = args.pallet_enum.as_deref() {
+            let pallets = construct_runtime_pallets(&content)?;
+            return Ok(pallet_enum_source(name, &pallets)?);
+        }
+        let call = config.clone().parse_expanded(&content)?;
+        if args.json {
+            Ok(call.to_json()?)
+        } else if args.abi {
+            Ok(call.to_abi_json()?)
+        } else if args.typescript {
+            Ok(call.to_typescript()?)
+        } else if let Some(module) = args.bench.as_deref() {
+            Ok(call.to_bench_source(module)?)
+        } else if let Some(include) = args.compile_test.as_deref() {
+            Ok(call.to_compile_test(include)?)
+        } else {
+            if args.verify {
+                verify(&call)?;
+            }
+            Ok(call.to_source()?)
+        }
+    };
+    let generated = match args.cache.as_ref() {
+        Some(dir) => {
+            let options = serde_json::to_vec(config.options())?;
+            let bench = args
+                .bench
+                .as_ref()
+                .map(|module| format!("bench {}", module));
+            let compile_test = args
+                .compile_test
+                .as_ref()
+                .map(|include| format!("compile-test {}", include));
+            let pallet_enum = args
+                .pallet_enum
+                .as_ref()
+                .map(|name| format!("pallet-enum {}", name));
+            let format: &[u8] = if let Some(pallet_enum) = pallet_enum.as_ref() {
+                pallet_enum.as_bytes()
+            } else if args.json {
+                b"json"
+            } else if args.abi {
+                b"abi"
+            } else if args.typescript {
+                b"typescript"
+            } else if let Some(bench) = bench.as_ref() {
+                bench.as_bytes()
+            } else if let Some(compile_test) = compile_test.as_ref() {
+                compile_test.as_bytes()
+            } else if args.verify {
+                // only verified output is cached under this key
+                b"rust verify"
+            } else {
+                b"rust"
+            };
+            let key = Cache::key([content.as_bytes(), options.as_slice(), format]);
+            Cache::new(dir).get_or_insert_with(key, generate)?
+        }
+        None => generate()?,
+    };
+
+    match args.output.as_deref() {
+        None | Some("-") if args.check => anyhow::bail!("`--check` requires an `--output` file"),
+        Some(path) if args.check => {
+            let existing = std::fs::read_to_string(path).unwrap_or_default();
+            if existing != generated && args.abi {
+                anyhow::bail!(
+                    "The call ABI differs from the committed {}, the changes can break clients:\n{}",
+                    path,
+                    abi_changes(&existing, &generated)
+                );
+            } else if existing != generated {
+                anyhow::bail!(
+                    "{} is not up to date, run without `--check` to update it",
+                    path
+                );
+            }
+        }
+        None | Some("-") => {
+            let mut stdout = std::io::stdout();
+            stdout.write_all(generated.as_bytes())?;
+            stdout.write_all(b"\n")?;
+        }
+        Some(path) => std::fs::write(path, generated)
+            .with_context(|| format!("Failed to write output {}", path))?,
+    }
+    Ok(0)
+}
@@ -0,0 +1,110 @@
+//! A directory that maps hashed inputs to their generated output
+
+use crate::blake2_256;
+use std::path::{Path, PathBuf};
+
+/// Caches generated code by the hash of everything it was generated from
+///
+/// Every entry is a file named after the hex encoded key in the cache
+/// directory. The version of this crate is part of every key, so upgrading
+/// invalidates all entries.
+///
+/// # Example
+///
+/// ```no_run
+/// # use substrate_pallet_call_extract::{Cache, PalletCallConfig};
+/// let source = std::fs::read_to_string("balances_call.rs").unwrap();
+/// let cache = Cache::new("target/call-extract");
+/// let key = Cache::key([source.as_bytes()]);
+/// let output = cache
+///     .get_or_insert_with(key, || {
+///         Ok(PalletCallConfig::default().parse(&source)?.expand()?.to_string())
+///     })
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+    /// The directory of the cached outputs
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// Uses the given directory for the cached outputs, it's created on the
+    /// first insert
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Cache { dir: dir.into() }
+    }
+
+    /// The directory of the cached outputs
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Hashes all the inputs into a key
+    pub fn key<'a>(inputs: impl IntoIterator<Item = &'a [u8]>) -> [u8; 32] {
+        let mut data = env!("CARGO_PKG_VERSION").as_bytes().to_vec();
+        for input in inputs {
+            // prefix with the length so that the inputs can't be shifted
+            data.extend_from_slice(&(input.len() as u64).to_le_bytes());
+            data.extend_from_slice(input);
+        }
+        blake2_256(&data)
+    }
+
+    /// Returns the cached output for the key, if any
+    pub fn get(&self, key: [u8; 32]) -> Option<String> {
+        std::fs::read_to_string(self.path(key)).ok()
+    }
+
+    /// Stores the output for the key
+    pub fn insert(&self, key: [u8; 32], output: &str) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(key), output)
+    }
+
+    /// Returns the cached output for the key or generates and stores it
+    pub fn get_or_insert_with<F>(&self, key: [u8; 32], generate: F) -> anyhow::Result<String>
+    where
+        F: FnOnce() -> anyhow::Result<String>,
+    {
+        if let Some(output) = self.get(key) {
+            return Ok(output);
+        }
+        let output = generate()?;
+        self.insert(key, &output)?;
+        Ok(output)
+    }
+
+    fn path(&self, key: [u8; 32]) -> PathBuf {
+        let name = key.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        self.dir.join(name).with_extension("rs")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_cache_outputs() {
+        let cache = Cache::new(crate::test_dir("cache"));
+        let key = Cache::key(["pub enum Call<T: Config> {}".as_bytes()]);
+        let _ = std::fs::remove_dir_all(cache.dir());
+
+        assert_eq!(cache.get(key), None);
+        let output = cache
+            .get_or_insert_with(key, || Ok("pub enum Call {}".to_string()))
+            .unwrap();
+        assert_eq!(output, "pub enum Call {}");
+        let output = cache
+            .get_or_insert_with(key, || panic!("output is cached"))
+            .unwrap();
+        assert_eq!(output, "pub enum Call {}");
+        assert_ne!(
+            key,
+            Cache::key(["pub enum Call<T: Config> {".as_bytes(), "}".as_bytes()])
+        );
+
+        let _ = std::fs::remove_dir_all(cache.dir());
+    }
+}
@@ -0,0 +1,106 @@
+//! Emits a compile-pass test for the generated code
+
+use crate::{ExpandedCall, Result};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Fields;
+
+/// Emits a `tests/ui` style compile-pass test, which includes the generated
+/// file and instantiates every variant with a distinct dummy type per generic
+///
+/// `include` is the path of the generated file, relative to the test.
+pub(crate) fn emit(
+    call: &ExpandedCall,
+    module: Option<&str>,
+    include: &str,
+) -> Result<TokenStream> {
+    let name = &call.name;
+    let path = match module {
+        Some(module) => {
+            let module = crate::parse_ident(module)?;
+            quote! { #module::#name }
+        }
+        None => quote! { #name },
+    };
+    let lifetimes = call.lifetimes.iter().map(|_| quote! { 'static });
+    let dummies = call
+        .generics
+        .values()
+        .map(|generic| format_ident!("Dummy{}", generic))
+        .collect::<Vec<_>>();
+    let params = lifetimes
+        .chain(dummies.iter().map(|dummy| quote! { #dummy }))
+        .collect::<Vec<_>>();
+    let ty = if params.is_empty() {
+        quote! { #path }
+    } else {
+        quote! { #path<#( #params ),*> }
+    };
+
+    let variants = call.variants.iter().map(|v| {
+        let ident = &v.variant.ident;
+        let value = match &v.variant.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote! { { #( #names: unimplemented!() ),* } }
+            }
+            Fields::Unnamed(fields) => {
+                let values = fields.unnamed.iter().map(|_| quote! { unimplemented!() });
+                quote! { ( #( #values ),* ) }
+            }
+            Fields::Unit => quote! {},
+        };
+        quote! { #path::#ident #value }
+    });
+    let instantiate = format_ident!("instantiate_{}", name.to_string().to_lowercase());
+
+    Ok(quote! {
+        #![allow(dead_code, unused_imports)]
+
+        include!(#include);
+
+        #(
+            #[derive(Clone, Debug, Default, PartialEq, Eq)]
+            struct #dummies;
+        )*
+
+        /// Never called, the variants only need to type-check
+        #[allow(dead_code, unreachable_code)]
+        fn #instantiate() -> Vec<#ty> {
+            vec![ #( #variants ),* ]
+        }
+
+        fn main() {}
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PalletCallConfig;
+
+    #[test]
+    fn can_emit_compile_test() {
+        let call = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand_call()
+            .unwrap();
+        let test = emit(&call, Some("balances"), "../../src/balances.rs")
+            .unwrap()
+            .to_string();
+        let expected = quote! {
+            #[allow(dead_code, unreachable_code)]
+            fn instantiate_call() -> Vec<balances::Call<DummyAccountId, DummyBalance> > {
+                vec![balances::Call::Transfer(unimplemented!(), unimplemented!())]
+            }
+        };
+        assert!(test.contains(&expected.to_string()));
+    }
+}
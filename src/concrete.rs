@@ -0,0 +1,138 @@
+//! Emits a concrete version of the generic `Call` enum
+
+use crate::options::{self, ConcreteCall};
+use crate::transform::fields_mut;
+use crate::{Error, ExpandedCall, Result};
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use std::collections::BTreeMap;
+use syn::{Fields, Ident, Type, Variant, Visibility};
+
+/// Emits the concrete enum and the `From` conversions from and into the
+/// generic enum
+///
+/// The `variants` are the variants of the generic enum, as they are emitted.
+pub(crate) fn emit(
+    call: &ExpandedCall,
+    concrete: &ConcreteCall,
+    variants: &[Variant],
+    repr: &TokenStream,
+    vis: &Visibility,
+) -> Result<TokenStream> {
+    let types = concrete.parse_types()?;
+    let name = &call.name;
+    let concrete_name =
+        options::parse_config("concrete name", &concrete.name, syn::parse_str::<Ident>)?;
+    if concrete_name == *name {
+        return Err(Error::Config(format!(
+            "the concrete enum can't be named like the generic enum `{}`",
+            name
+        )));
+    }
+
+    let args = call
+        .generics
+        .values()
+        .map(|generic| {
+            types
+                .get(generic.as_str())
+                .ok_or_else(|| Error::Config(format!("no concrete type for generic `{}`", generic)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let generic_ty = if args.is_empty() {
+        quote! { #name }
+    } else {
+        quote! { #name<#( #args ),*> }
+    };
+
+    let mut concrete_variants = Vec::with_capacity(variants.len());
+    let mut idents = Vec::with_capacity(variants.len());
+    let mut patterns = Vec::with_capacity(variants.len());
+    for variant in variants {
+        let mut concrete_variant = variant.clone();
+        for field in fields_mut(&mut concrete_variant) {
+            let ty = &field.ty;
+            field.ty = syn::parse2::<Type>(substitute(quote! { #ty }, &types))?;
+        }
+        concrete_variants.push(concrete_variant);
+        idents.push(&variant.ident);
+        patterns.push(pattern(&variant.fields));
+    }
+
+    let attrs = &call.attrs;
+    Ok(quote! {
+        #( #attrs )*
+        #repr
+        #vis enum #concrete_name {
+            #( #concrete_variants ),*
+        }
+
+        impl From<#generic_ty> for #concrete_name {
+            fn from(call: #generic_ty) -> Self {
+                match call {
+                    #( #name::#idents #patterns => #concrete_name::#idents #patterns, )*
+                }
+            }
+        }
+
+        impl From<#concrete_name> for #generic_ty {
+            fn from(call: #concrete_name) -> Self {
+                match call {
+                    #( #concrete_name::#idents #patterns => #name::#idents #patterns, )*
+                }
+            }
+        }
+    })
+}
+
+/// Binds all fields, in a form that can be used as pattern and expression
+pub(crate) fn pattern(fields: &Fields) -> TokenStream {
+    match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote! { { #( #names ),* } }
+        }
+        Fields::Unnamed(fields) => {
+            let names = (0..fields.unnamed.len()).map(|idx| format_ident!("f{}", idx));
+            quote! { ( #( #names ),* ) }
+        }
+        Fields::Unit => quote! {},
+    }
+}
+
+/// Replaces every generic in the tokens with its concrete type
+pub(crate) fn substitute(tokens: TokenStream, types: &BTreeMap<&str, Type>) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|tt| match tt {
+            TokenTree::Ident(ident) => match types.get(ident.to_string().as_str()) {
+                Some(ty) => quote! { #ty },
+                None => TokenTree::Ident(ident).into(),
+            },
+            TokenTree::Group(group) => {
+                let mut substituted =
+                    Group::new(group.delimiter(), substitute(group.stream(), types));
+                substituted.set_span(group.span());
+                TokenTree::Group(substituted).into()
+            }
+            tt => tt.into(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_substitute_nested_generics() {
+        let types = vec![("Balance", syn::parse_quote!(u128))]
+            .into_iter()
+            .collect();
+        let tokens = substitute(quote! { codec::Compact<Balance> }, &types);
+        assert_eq!(
+            tokens.to_string(),
+            quote! { codec::Compact<u128> }.to_string()
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Structured changes between two versions of a `pallet::Call`
+
+use crate::{ArgInfo, CallInfo, PalletCall, Result};
+use std::fmt;
+
+/// A change of a single call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallChange {
+    /// The call is new
+    Added(CallInfo),
+    /// The call was removed
+    Removed(CallInfo),
+    /// The call was renamed, its index and parameters are unchanged
+    Renamed {
+        /// The previous name
+        from: String,
+        /// The new name
+        to: String,
+    },
+    /// The index of the call changed
+    IndexChanged {
+        /// The name of the call
+        name: String,
+        /// The previous index
+        from: u8,
+        /// The new index
+        to: u8,
+    },
+    /// The parameters of the call changed
+    ArgsChanged {
+        /// The name of the call
+        name: String,
+        /// The previous parameters
+        from: Vec<ArgInfo>,
+        /// The new parameters
+        to: Vec<ArgInfo>,
+    },
+}
+
+impl CallChange {
+    /// How the change affects users of the call surface
+    ///
+    /// Renames keep the encoding, added calls only extend it. Removed calls,
+    /// index changes and parameter changes break the encoding of existing
+    /// calls, which also covers added calls that reuse the index of a removed
+    /// or moved call.
+    pub fn compatibility(&self) -> Compatibility {
+        match self {
+            CallChange::Added(_) => Compatibility::Additive,
+            CallChange::Renamed { .. } => Compatibility::Compatible,
+            CallChange::Removed(_)
+            | CallChange::IndexChanged { .. }
+            | CallChange::ArgsChanged { .. } => Compatibility::Breaking,
+        }
+    }
+}
+
+/// The semver-style classification of changes to the call surface, ordered
+/// by severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Compatibility {
+    /// The calls are unchanged
+    Unchanged,
+    /// The encoding of all calls is unchanged, like renamed calls
+    Compatible,
+    /// Calls were added, existing calls are unchanged
+    Additive,
+    /// The encoding of existing calls changed
+    Breaking,
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compatibility::Unchanged => write!(f, "unchanged"),
+            Compatibility::Compatible => write!(f, "compatible"),
+            Compatibility::Additive => write!(f, "additive"),
+            Compatibility::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+/// All changes between two versions of a `pallet::Call`
+///
+/// The `Display` implementation renders a changelog with one line per change.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallDiff {
+    /// All changes, changed calls first in the order of the old version,
+    /// followed by renamed, removed and added calls
+    pub changes: Vec<CallChange>,
+}
+
+impl CallDiff {
+    /// Whether both versions have the same calls
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The most severe classification of all changes
+    pub fn compatibility(&self) -> Compatibility {
+        self.changes
+            .iter()
+            .map(CallChange::compatibility)
+            .max()
+            .unwrap_or(Compatibility::Unchanged)
+    }
+}
+
+/// Compares the calls of two versions of a `pallet::Call`
+///
+/// A removed and an added call with the same index and parameters are
+/// reported as renamed.
+pub fn diff(old: &PalletCall, new: &PalletCall) -> Result<CallDiff> {
+    Ok(diff_calls(&old.calls()?, &new.calls()?, |old, new| {
+        old == new
+    }))
+}
+
+/// Compares two lists of calls, `same_args` decides whether the parameters
+/// of a call are unchanged
+pub(crate) fn diff_calls<F>(old: &[CallInfo], new: &[CallInfo], same_args: F) -> CallDiff
+where
+    F: Fn(&[ArgInfo], &[ArgInfo]) -> bool,
+{
+    let mut changes = Vec::new();
+    for prev in old {
+        if let Some(next) = new.iter().find(|c| c.name == prev.name) {
+            if prev.index != next.index {
+                changes.push(CallChange::IndexChanged {
+                    name: prev.name.clone(),
+                    from: prev.index,
+                    to: next.index,
+                });
+            }
+            if !same_args(&prev.args, &next.args) {
+                changes.push(CallChange::ArgsChanged {
+                    name: prev.name.clone(),
+                    from: prev.args.clone(),
+                    to: next.args.clone(),
+                });
+            }
+        }
+    }
+
+    let mut removed = old
+        .iter()
+        .filter(|prev| !new.iter().any(|c| c.name == prev.name))
+        .collect::<Vec<_>>();
+    let mut added = new
+        .iter()
+        .filter(|next| !old.iter().any(|c| c.name == next.name))
+        .collect::<Vec<_>>();
+
+    removed.retain(|prev| {
+        let renamed = added
+            .iter()
+            .position(|next| next.index == prev.index && same_args(&prev.args, &next.args));
+        if let Some(pos) = renamed {
+            let next = added.remove(pos);
+            changes.push(CallChange::Renamed {
+                from: prev.name.clone(),
+                to: next.name.clone(),
+            });
+            false
+        } else {
+            true
+        }
+    });
+
+    changes.extend(removed.into_iter().cloned().map(CallChange::Removed));
+    changes.extend(added.into_iter().cloned().map(CallChange::Added));
+    CallDiff { changes }
+}
+
+/// Renders the parameters like `(T::AccountId, compact T::Balance)`
+fn args(args: &[ArgInfo]) -> String {
+    let args = args.iter().map(ToString::to_string).collect::<Vec<_>>();
+    format!("({})", args.join(", "))
+}
+
+impl fmt::Display for CallChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallChange::Added(call) => write!(
+                f,
+                "added `{}{}` with index {}",
+                call.name,
+                args(&call.args),
+                call.index
+            ),
+            CallChange::Removed(call) => write!(f, "removed `{}`", call.name),
+            CallChange::Renamed { from, to } => write!(f, "renamed `{}` to `{}`", from, to),
+            CallChange::IndexChanged { name, from, to } => {
+                write!(f, "changed index of `{}` from {} to {}", name, from, to)
+            }
+            CallChange::ArgsChanged { name, from, to } => write!(
+                f,
+                "changed parameters of `{}` from `{}` to `{}`",
+                name,
+                args(from),
+                args(to)
+            ),
+        }
+    }
+}
+
+impl fmt::Display for CallDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "- {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PalletCallConfig;
+
+    #[test]
+    fn can_diff_calls() {
+        let old = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+                set_balance(T::AccountId, T::Balance),
+                remark(Vec<u8>),
+            }
+        "#,
+            )
+            .unwrap();
+        let new = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer_allow_death(T::AccountId, #[codec(compact)] T::Balance),
+                set_balance(T::AccountId, #[codec(compact)] T::Balance),
+                #[codec(index = 5)]
+                remark(Vec<u8>),
+                burn(#[codec(compact)] T::Balance),
+            }
+        "#,
+            )
+            .unwrap();
+
+        let diff = diff(&old, &new).unwrap();
+        assert_eq!(
+            diff.to_string(),
+            "- changed parameters of `set_balance` from `(T::AccountId, T::Balance)` to `(T::AccountId, compact T::Balance)`
+- changed index of `remark` from 2 to 5
+- renamed `transfer` to `transfer_allow_death`
+- added `burn(compact T::Balance)` with index 3
+"
+        );
+        assert_eq!(diff.compatibility(), Compatibility::Breaking);
+        assert!(super::diff(&old, &old).unwrap().is_empty());
+    }
+
+    #[test]
+    fn can_classify_compatibility() {
+        let call = |name: &str, index| CallInfo {
+            name: name.to_string(),
+            index,
+            args: Vec::new(),
+            docs: Vec::new(),
+            origin: None,
+            weight: None,
+        };
+        let diff = |old: &[CallInfo], new: &[CallInfo]| diff_calls(old, new, |a, b| a == b);
+
+        let old = [call("transfer", 0)];
+        assert_eq!(diff(&old, &old).compatibility(), Compatibility::Unchanged);
+        assert_eq!(
+            diff(&old, &[call("transfer_allow_death", 0)]).compatibility(),
+            Compatibility::Compatible
+        );
+        assert_eq!(
+            diff(&old, &[call("transfer", 0), call("burn", 1)]).compatibility(),
+            Compatibility::Additive
+        );
+        assert_eq!(
+            diff(&old, &[call("transfer", 1), call("burn", 0)]).compatibility(),
+            Compatibility::Breaking
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Extracts the docs of the dispatchables from the un-expanded source of the
+//! pallet
+
+use crate::{attributes, origin, Error, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+use syn::ext::IdentExt;
+use syn::{ImplItem, Item};
+
+/// Returns the doc lines of every dispatchable of the `#[pallet::call]` impl
+/// by the name of the dispatchable
+pub(crate) fn call_docs(source: &str) -> Result<BTreeMap<String, Vec<String>>> {
+    let file = syn::parse_file(source)?;
+    let mut docs = BTreeMap::new();
+    collect_docs(&file.items, &mut docs);
+    Ok(docs)
+}
+
+/// Reads the pallet source from the file and extracts the docs of its
+/// dispatchables, see [`call_docs`]
+pub(crate) fn call_docs_from_path(path: &Path) -> Result<BTreeMap<String, Vec<String>>> {
+    let source = std::fs::read_to_string(path).map_err(|err| {
+        Error::Config(format!(
+            "failed to read pallet source {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    call_docs(&source)
+}
+
+fn collect_docs(items: &[Item], docs: &mut BTreeMap<String, Vec<String>>) {
+    for item in items {
+        match item {
+            Item::Mod(module) => {
+                if let Some((_, items)) = module.content.as_ref() {
+                    collect_docs(items, docs);
+                }
+            }
+            Item::Impl(item) if item.attrs.iter().any(origin::is_pallet_call) => {
+                for item in &item.items {
+                    if let ImplItem::Method(method) = item {
+                        let lines = attributes::doc_lines(&method.attrs);
+                        if !lines.is_empty() {
+                            docs.insert(method.sig.ident.unraw().to_string(), lines);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the docs of the arguments that are listed in the docs of a
+/// dispatchable, by argument name
+///
+/// Arguments are documented by convention as list items like
+/// ``- `dest`: The recipient`` or ``* `value` - The amount``.
+pub(crate) fn argument_docs(lines: &[String]) -> BTreeMap<String, String> {
+    let mut args = BTreeMap::new();
+    for line in lines {
+        let line = line.trim();
+        let item = match line
+            .strip_prefix("- `")
+            .or_else(|| line.strip_prefix("* `"))
+        {
+            Some(item) => item,
+            None => continue,
+        };
+        let (name, doc) = match item.split_once('`') {
+            Some(split) => split,
+            None => continue,
+        };
+        let doc =
+            doc.trim_start_matches(|c: char| c == ':' || c == '-' || c == ',' || c.is_whitespace());
+        if !name.is_empty() && !doc.is_empty() {
+            args.insert(name.to_string(), doc.to_string());
+        }
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_extract_call_docs() {
+        let docs = call_docs(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {
+                #[pallet::call]
+                impl<T: Config> Pallet<T> {
+                    /// Transfer some balance
+                    ///
+                    /// - `dest`: The recipient
+                    /// * `value` - The amount
+                    pub fn transfer(origin: OriginFor<T>, dest: T::AccountId, value: T::Balance) -> DispatchResult {
+                        Ok(())
+                    }
+
+                    pub fn remark(origin: OriginFor<T>, remark: Vec<u8>) -> DispatchResult {
+                        Ok(())
+                    }
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        assert_eq!(docs.len(), 1);
+
+        let args = argument_docs(&docs["transfer"]);
+        assert_eq!(args["dest"], "The recipient");
+        assert_eq!(args["value"], "The amount");
+    }
+}
@@ -0,0 +1,278 @@
+//! The error and warning types of the crate
+
+use crate::Finding;
+use proc_macro2::{Span, TokenStream};
+use std::fmt;
+
+/// A specialized `Result` type for expanding a `pallet::Call`
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors that can occur when parsing or expanding a `pallet::Call`
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to parse the `pallet::Call`
+    Parse(syn::Error),
+    /// A call parameter has a type that can't be expanded
+    UnsupportedType {
+        /// The original name of the call
+        call: String,
+        /// The type of the parameter
+        ty: String,
+        /// The location of the type in the `pallet::Call`
+        location: Location,
+    },
+    /// A call parameter is a reference without a named lifetime, which can't
+    /// be declared by the generated enum
+    ElidedLifetime {
+        /// The original name of the call
+        call: String,
+        /// The name or the position of the field
+        field: String,
+        /// The location of the type in the `pallet::Call`
+        location: Location,
+    },
+    /// Multiple items would be emitted with the same identifier
+    Collision {
+        /// The colliding identifier
+        name: String,
+        /// The original names of all the colliding items
+        originals: Vec<String>,
+        /// The location of the item that collided in the `pallet::Call`
+        location: Location,
+    },
+    /// A configured value is invalid
+    Config(String),
+    /// A warning that was turned into an error with `deny_warnings`
+    Warning(Warning),
+    /// The generated enum failed the validation of `strict`
+    Invalid(Vec<Finding>),
+    /// Multiple errors, collected in a single pass
+    Multiple(Vec<Error>),
+}
+
+impl Error {
+    /// Combines all errors into a single error
+    ///
+    /// Returns `Ok(())` if there are no errors.
+    pub fn combine(errors: impl IntoIterator<Item = Error>) -> Result<()> {
+        let mut errors = errors
+            .into_iter()
+            .flat_map(Error::into_errors)
+            .collect::<Vec<_>>();
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+
+    /// Returns the location of the error in the `pallet::Call`
+    ///
+    /// This is `Span::call_site()` if the error has no location.
+    pub fn span(&self) -> Span {
+        match self {
+            Error::Parse(err) => err.span(),
+            Error::UnsupportedType { location, .. }
+            | Error::ElidedLifetime { location, .. }
+            | Error::Collision { location, .. } => location.span(),
+            Error::Config(_) => Span::call_site(),
+            Error::Warning(warning) => warning.location.span(),
+            Error::Invalid(findings) => findings
+                .first()
+                .map(|finding| finding.location.span())
+                .unwrap_or_else(Span::call_site),
+            Error::Multiple(errors) => errors
+                .first()
+                .map(Error::span)
+                .unwrap_or_else(Span::call_site),
+        }
+    }
+
+    /// Converts the error into a `syn::Error` that points at the offending
+    /// item of the `pallet::Call`, multiple errors are combined
+    pub fn to_syn_error(&self) -> syn::Error {
+        match self {
+            Error::Parse(err) => err.clone(),
+            Error::Multiple(errors) => {
+                let mut errors = errors.iter().map(Error::to_syn_error);
+                let mut combined = errors
+                    .next()
+                    .unwrap_or_else(|| syn::Error::new(Span::call_site(), "Multiple errors"));
+                for err in errors {
+                    combined.combine(err);
+                }
+                combined
+            }
+            err => syn::Error::new(err.span(), err),
+        }
+    }
+
+    /// Renders the error as `compile_error!` invocations, to be returned from
+    /// a proc macro
+    pub fn to_compile_error(&self) -> TokenStream {
+        self.to_syn_error().to_compile_error()
+    }
+
+    /// Returns all individual errors
+    pub fn into_errors(self) -> Vec<Error> {
+        match self {
+            Error::Multiple(errors) => errors.into_iter().flat_map(Error::into_errors).collect(),
+            err => vec![err],
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "Failed to parse: {}", err),
+            Error::UnsupportedType { call, ty, .. } => write!(
+                f,
+                "Unsupported type `{}` in call `{}`, only TypePaths are supported currently",
+                ty, call
+            ),
+            Error::ElidedLifetime { call, field, .. } => write!(
+                f,
+                "Reference without a named lifetime in field `{}` of call `{}`, use a named lifetime like `&'a` or an owned type",
+                field, call
+            ),
+            Error::Collision {
+                name, originals, ..
+            } => write!(
+                f,
+                "`{}` would be emitted for all of `{}`",
+                name,
+                originals.join("`, `")
+            ),
+            Error::Config(msg) => write!(f, "Invalid configuration: {}", msg),
+            Error::Warning(warning) => write!(f, "{}", warning),
+            Error::Invalid(findings) => {
+                write!(f, "Validation failed:")?;
+                for finding in findings {
+                    write!(f, "\n  {}", finding)?;
+                }
+                Ok(())
+            }
+            Error::Multiple(errors) => {
+                for (idx, err) in errors.iter().enumerate() {
+                    if idx > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", err)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Information of the `pallet::Call` that is dropped during expansion
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// The category of the warning
+    pub kind: WarningKind,
+    /// The original name of the affected call
+    pub call: String,
+    /// What was dropped
+    pub message: String,
+    /// The location of the dropped item in the `pallet::Call`
+    pub location: Location,
+}
+
+impl Warning {
+    pub(crate) fn new(kind: WarningKind, call: &str, message: String, span: Span) -> Self {
+        Warning {
+            kind,
+            call: call.to_string(),
+            message,
+            location: Location::new(span),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in call `{}`", self.message, self.call)
+    }
+}
+
+/// The category of a `Warning`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// Doc comments were removed
+    DroppedDocs,
+    /// An attribute was removed by the attribute filters
+    DroppedAttribute,
+    /// A call is not part of the generated enum
+    SkippedCall,
+    /// A call can't be expanded and was skipped in lenient mode
+    InvalidCall,
+}
+
+/// The location of an error in the `pallet::Call`
+///
+/// Spans are only valid on the thread they were created on, this keeps the
+/// `Error` `Send` and `Sync`.
+#[derive(Debug, Clone)]
+pub struct Location(syn::Error);
+
+impl Location {
+    pub(crate) fn new(span: Span) -> Self {
+        Location(syn::Error::new(span, ""))
+    }
+
+    /// Returns the span of the location, or `Span::call_site()` if called
+    /// from a different thread than the one the location was created on
+    pub fn span(&self) -> Span {
+        self.0.span()
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<syn::Error> for Error {
+    fn from(err: syn::Error) -> Self {
+        Error::Parse(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_combine_errors() {
+        assert!(Error::combine(vec![]).is_ok());
+
+        let err = Error::combine(vec![
+            Error::Config("a".to_string()),
+            Error::Multiple(vec![
+                Error::Config("b".to_string()),
+                Error::Config("c".to_string()),
+            ]),
+        ])
+        .unwrap_err();
+        assert_eq!(err.into_errors().len(), 3);
+    }
+
+    #[test]
+    fn can_convert_to_compile_error() {
+        let err = Error::Multiple(vec![
+            Error::Config("a".to_string()),
+            Error::UnsupportedType {
+                call: "remark".to_string(),
+                ty: "&[u8]".to_string(),
+                location: Location::new(Span::call_site()),
+            },
+        ]);
+        let tokens = err.to_compile_error().to_string();
+        assert_eq!(tokens.matches("compile_error").count(), 2);
+    }
+}
@@ -0,0 +1,187 @@
+//! Generates tests from call data of a real chain
+
+use crate::{Error, ExpandedCall, Result};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::BTreeMap;
+use std::path::Path;
+use syn::{Expr, Fields, Ident, Visibility};
+
+/// Reads the hex encoded call data samples by call name from a `.json` or
+/// `.toml` file
+pub(crate) fn read_hex_fixtures(path: &Path) -> Result<BTreeMap<String, String>> {
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        Error::Config(format!(
+            "failed to read fixtures {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    let fixtures = if path
+        .extension()
+        .map(|ext| ext == "json")
+        .unwrap_or_default()
+    {
+        serde_json::from_str(&content).map_err(|err| err.to_string())
+    } else {
+        toml::from_str(&content).map_err(|err| err.to_string())
+    };
+    fixtures.map_err(|err| Error::Config(format!("invalid fixtures {}: {}", path.display(), err)))
+}
+
+/// Decodes a hex string with an optional `0x` prefix
+pub(crate) fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok())
+        .collect()
+}
+
+/// Emits a test module with a test for every sample, which asserts that the
+/// call data decodes to the variant of the call with the concrete enum
+pub(crate) fn emit_decode_tests(
+    call: &ExpandedCall,
+    concrete_name: &Ident,
+    codec_crate: &syn::Path,
+    fixtures: &BTreeMap<String, String>,
+) -> Result<TokenStream> {
+    let mut tests = Vec::with_capacity(fixtures.len());
+    for (call_name, hex) in fixtures {
+        let variant = call
+            .variants
+            .iter()
+            .find(|v| v.call_name == *call_name)
+            .ok_or_else(|| {
+                Error::Config(format!("no call `{}` for the decode fixture", call_name))
+            })?;
+        let bytes = decode_hex(hex).ok_or_else(|| {
+            Error::Config(format!(
+                "the decode fixture of `{}` is not valid hex",
+                call_name
+            ))
+        })?;
+        let test = format_ident!("decodes_{}", call_name);
+        let ident = &variant.variant.ident;
+        let message = format!("failed to decode `{}`", call_name);
+        tests.push(quote! {
+            #[test]
+            fn #test() {
+                let mut input: &[u8] = &[#( #bytes ),*];
+                let call = <#concrete_name as #codec_crate::Decode>::decode(&mut input).expect(#message);
+                assert!(input.is_empty(), "trailing bytes after the call");
+                assert!(matches!(call, #concrete_name::#ident { .. }));
+            }
+        });
+    }
+    Ok(quote! {
+        #[cfg(test)]
+        mod decode_fixtures {
+            use super::*;
+
+            #( #tests )*
+        }
+    })
+}
+
+/// Emits an example of every call with the concrete enum and functions to
+/// encode them
+///
+/// The arguments of a call are the `example_args` of the call, or
+/// `Default::default()`.
+pub(crate) fn emit_examples(
+    call: &ExpandedCall,
+    concrete_name: &Ident,
+    codec_crate: &syn::Path,
+    example_args: &BTreeMap<String, Vec<String>>,
+    vis: &Visibility,
+) -> Result<TokenStream> {
+    if let Some(name) = example_args
+        .keys()
+        .find(|name| !call.variants.iter().any(|v| v.call_name == **name))
+    {
+        return Err(Error::Config(format!(
+            "no call `{}` for the example arguments",
+            name
+        )));
+    }
+
+    let mut examples = Vec::with_capacity(call.variants.len());
+    for v in &call.variants {
+        let len = v.variant.fields.len();
+        let args = match example_args.get(&v.call_name) {
+            Some(args) if args.len() != len => {
+                return Err(Error::Config(format!(
+                    "the call `{}` has {} arguments, but {} example arguments are set",
+                    v.call_name,
+                    len,
+                    args.len()
+                )))
+            }
+            Some(args) => args
+                .iter()
+                .map(|arg| crate::options::parse_config("example argument", arg, syn::parse_str))
+                .collect::<Result<Vec<Expr>>>()?,
+            None => vec![syn::parse_quote!(Default::default()); len],
+        };
+        let ident = &v.variant.ident;
+        let value = match &v.variant.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote! { { #( #names: #args ),* } }
+            }
+            Fields::Unnamed(_) => quote! { ( #( #args ),* ) },
+            Fields::Unit => quote! {},
+        };
+        let call_name = &v.call_name;
+        examples.push(quote! { (#call_name, #concrete_name::#ident #value) });
+    }
+
+    Ok(quote! {
+        impl #concrete_name {
+            /// An example of every call by its name
+            #vis fn examples() -> Vec<(&'static str, Self)> {
+                vec![ #( #examples ),* ]
+            }
+
+            /// The SCALE encoding of every example by the name of its call
+            #vis fn encoded_examples() -> Vec<(&'static str, Vec<u8>)> {
+                Self::examples()
+                    .into_iter()
+                    .map(|(name, call)| (name, #codec_crate::Encode::encode(&call)))
+                    .collect()
+            }
+
+            /// The hex encoded examples as JSON object by the name of their
+            /// call
+            #[cfg(feature = "std")]
+            #vis fn encoded_examples_json() -> String {
+                let entries = Self::encoded_examples()
+                    .into_iter()
+                    .map(|(name, bytes)| {
+                        let hex = bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+                        format!("  \"{}\": \"0x{}\"", name, hex)
+                    })
+                    .collect::<Vec<_>>();
+                format!("{{\n{}\n}}\n", entries.join(",\n"))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_decode_hex() {
+        assert_eq!(decode_hex("0x0a00ff"), Some(vec![0x0a, 0x00, 0xff]));
+        assert_eq!(decode_hex("0A"), Some(vec![0x0a]));
+        assert_eq!(decode_hex("0x0"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! Structured information about the calls of a `pallet::Call`
+
+use crate::{attributes, type_to_string, CallWeight, ExpandedVariant, OriginKind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The metadata of an expanded `pallet::Call`, as exported by
+/// `PalletCall::to_json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallMetadata {
+    /// The version of the format
+    pub version: u32,
+    /// The name of the generated enum
+    pub name: String,
+    /// The original type of every generic of the generated enum, by generic
+    /// name
+    pub generics: BTreeMap<String, String>,
+    /// All calls of the generated enum
+    pub calls: Vec<CallInfo>,
+}
+
+impl CallMetadata {
+    /// The current version of the format
+    pub const VERSION: u32 = 1;
+}
+
+/// The call ABI of an expanded `pallet::Call`, as exported by
+/// `PalletCall::to_abi_json`
+///
+/// This is the part of the `CallMetadata` that determines how the calls are
+/// encoded, plus the names of the enum, its calls and their parameters. Docs,
+/// origins and weights are left out, so a committed ABI doesn't change with
+/// them, but renaming a call or a parameter changes it even if the encoding
+/// stays the same.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallAbi {
+    /// The version of the format
+    pub version: u32,
+    /// The name of the generated enum
+    pub name: String,
+    /// The original type of every generic of the generated enum, by generic
+    /// name
+    pub generics: BTreeMap<String, String>,
+    /// All calls of the generated enum, sorted by index
+    pub calls: Vec<AbiCall>,
+}
+
+impl CallAbi {
+    /// The current version of the format
+    pub const VERSION: u32 = 1;
+}
+
+impl From<CallMetadata> for CallAbi {
+    fn from(metadata: CallMetadata) -> Self {
+        let mut calls = metadata
+            .calls
+            .into_iter()
+            .map(|call| AbiCall {
+                name: call.name,
+                index: call.index,
+                args: call.args,
+            })
+            .collect::<Vec<_>>();
+        calls.sort_by_key(|call| call.index);
+        CallAbi {
+            version: CallAbi::VERSION,
+            name: metadata.name,
+            generics: metadata.generics,
+            calls,
+        }
+    }
+}
+
+/// A dispatchable in the `CallAbi`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiCall {
+    /// The original name of the call, like `transfer`
+    pub name: String,
+    /// The index of the call, as it is encoded
+    pub index: u8,
+    /// The parameters of the call, without the origin
+    pub args: Vec<ArgInfo>,
+}
+
+/// A dispatchable of the `pallet::Call`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallInfo {
+    /// The original name of the call, like `transfer`
+    pub name: String,
+    /// The index of the call, as it is encoded
+    pub index: u8,
+    /// The lines of the call's doc comment
+    pub docs: Vec<String>,
+    /// The parameters of the call, without the origin
+    pub args: Vec<ArgInfo>,
+    /// The origin the call requires, if it's classified from the pallet
+    /// source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<OriginKind>,
+    /// The benchmarked weight of the call, if it's parsed from the pallet's
+    /// `weights.rs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<CallWeight>,
+}
+
+/// A parameter of a dispatchable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArgInfo {
+    /// The name of the parameter, if the `pallet::Call` has named fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The original type, like `T::Balance`
+    pub ty: String,
+    /// Whether the parameter is marked `#[codec(compact)]`
+    pub compact: bool,
+}
+
+impl fmt::Display for ArgInfo {
+    /// Renders the parameter like `value: compact T::Balance`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = self.name.as_ref() {
+            write!(f, "{}: ", name)?;
+        }
+        if self.compact {
+            write!(f, "compact ")?;
+        }
+        write!(f, "{}", self.ty)
+    }
+}
+
+impl CallInfo {
+    /// The first paragraph of the docs as a single line
+    pub fn summary(&self) -> String {
+        self.docs
+            .iter()
+            .map(|line| line.trim())
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub(crate) fn new(variant: &ExpandedVariant) -> Self {
+        let docs = attributes::doc_lines(&variant.original.attrs)
+            .into_iter()
+            .map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+            .collect();
+        let args = variant
+            .original
+            .fields
+            .iter()
+            .filter(|field| !field.attrs.iter().any(attributes::is_skip))
+            .map(|field| ArgInfo {
+                name: field.ident.as_ref().map(ToString::to_string),
+                ty: type_to_string(&field.ty),
+                compact: field.attrs.iter().any(attributes::is_compact),
+            })
+            .collect();
+        CallInfo {
+            name: variant.call_name.clone(),
+            index: variant.index,
+            docs,
+            args,
+            origin: variant.origin.clone(),
+            weight: variant.weight,
+        }
+    }
+}
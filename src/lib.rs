@@ -1,354 +1,4571 @@
 //! Extract generated pallet code
 
+use blake2::{digest::consts::U32, Blake2b, Digest};
 use dep_expand::Expander;
 use inflector::Inflector;
-use proc_macro2::TokenStream;
-use quote::quote;
+use proc_macro2::{Literal, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use std::borrow::Cow;
 use std::collections::BTreeMap;
-use syn::spanned::Spanned;
+use std::sync::Arc;
 use syn::{
-    punctuated::Punctuated, Attribute, Fields, FieldsUnnamed, Ident, Path, PathSegment, Type,
-    TypePath, Variant,
+    Attribute, Expr, Field, Fields, Ident, ItemUse, Path, Type, TypePath, Variant, Visibility,
+    WherePredicate,
 };
 
+mod attributes;
+mod batch;
+mod bench;
+mod cache;
+mod compile_test;
+mod concrete;
+mod diff;
+mod docs;
+mod error;
+mod fixtures;
+mod introspect;
+mod lookup;
+mod manifest;
+mod markdown;
+mod options;
+mod origin;
+mod output;
+#[cfg(feature = "rpc")]
+mod rpc;
+mod runtime;
+mod schema;
+mod source_map;
+mod transform;
+mod typescript;
+mod validate;
+mod value;
+#[cfg(feature = "verify")]
+mod verify;
+mod versioned;
+mod weights;
+mod workspace;
+
+pub use batch::{CallSet, SharedGeneric};
+pub use cache::Cache;
+pub use diff::{diff, CallChange, CallDiff, Compatibility};
+pub use error::{Error, Location, Result, Warning, WarningKind};
+pub use introspect::{AbiCall, ArgInfo, CallAbi, CallInfo, CallMetadata};
+pub use manifest::{run_manifest, Manifest, PalletManifest};
+pub use options::{
+    CallFilterList, CallOptions, CallOverride, CfgPolicy, CompactMode, ConcreteCall,
+    ParameterStyle, Profile, ReferenceMode, RustfmtOptions, PROVENANCE_HEADER,
+};
+pub use origin::{call_origins, OriginKind};
+pub use output::WriteMode;
+#[cfg(feature = "rpc")]
+pub use rpc::{
+    fetch_metadata, fetch_metadata_at_version, metadata_calls, metadata_pallets, runtime_apis,
+    runtime_calls, verify_metadata, RuntimeApiInfo,
+};
+pub use runtime::{construct_runtime_pallets, emit_pallet_enum, pallet_enum_source, PalletIndex};
+pub use source_map::{FieldSource, SourceMap, SourceSpan, VariantSource};
+pub use transform::{CallTransform, ExpandedCall, ExpandedVariant, Pass};
+pub use validate::{Finding, FindingKind};
+#[cfg(feature = "verify")]
+pub use verify::Verifier;
+pub use versioned::VersionedCalls;
+pub use weights::{parse_weights, CallWeight};
+pub use workspace::{expand_crate, resolve_package, scan_workspace, PalletCrate};
+
+type Blake2b256 = Blake2b<U32>;
+
+/// Converts the name of a call to the name of its variant
+type NameConversion = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Converts the original type path of a generic to its name
+type GenericNameConversion = Arc<dyn Fn(&TypePath) -> String + Send + Sync>;
+
+/// Replaces the type of a field
+type TypeRewrite = Arc<dyn Fn(&Type) -> Option<Type> + Send + Sync>;
+
+/// Determines the attributes of a generated field
+type FieldAttributes = Arc<dyn Fn(&Field, &VariantInfo) -> Vec<Attribute> + Send + Sync>;
+
+/// Determines the additional attributes of a generated variant
+type VariantAttributes = Arc<dyn Fn(&VariantInfo) -> Vec<Attribute> + Send + Sync>;
+
+/// Determines the doc comment of a generated variant
+type DocsCallback = Arc<dyn Fn(&[String], &VariantInfo) -> Vec<String> + Send + Sync>;
+
 /// Additional parameters to configure the pallet expansion
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct PalletCallConfig {
-    /// Use this name for the Call enum, by default `Call` will be used
-    name: Option<String>,
+    /// All options that can be loaded from a config file
+    options: CallOptions,
     /// Use this variant conversion function, by default `CamelCase` will be
     /// used even if the pallet call variants are snake case
-    variant_name_conversion: Option<Box<dyn Fn(&str) -> String>>,
+    variant_name_conversion: Option<NameConversion>,
     /// Use this generic conversion function to modify The generic name
     /// by default the last type path segment is used: `T::Balance` -> `Balance`
-    generic_name_conversion: Option<Box<dyn Fn(&TypePath) -> String>>,
-    /// How to expand call parameters to variant fields
-    _call_parameter_style: ParameterStyle,
-    /// Whether to keep original comments
-    keep_comments: bool,
-    /// The name fo the scale codec crate by default it's `codec`
-    codec_crate: Option<String>,
-    /// Whether to derive runtime debug
-    runtime_debug: Option<String>,
-    /// Additional attributes
-    additional_attr: Vec<Attribute>,
-    /// Additional derives
-    additional_derives: Vec<Path>,
+    generic_name_conversion: Option<GenericNameConversion>,
+    /// Use this function to replace the types of the fields, before generics
+    /// are erased
+    type_rewrite: Option<TypeRewrite>,
+    /// Use this function to determine the attributes of the generated fields
+    field_attributes: Option<FieldAttributes>,
+    /// Use this function to add attributes to the generated variants
+    variant_attributes: Option<VariantAttributes>,
+    /// Use this function to determine the doc comments of the generated
+    /// variants
+    docs: Option<DocsCallback>,
+    /// Use this function to modify the generated items before they're wrapped
+    /// in a module
+    post_process: Option<Arc<dyn Fn(TokenStream) -> TokenStream + Send + Sync>>,
+    /// Additional passes, each run after the built-in pass it's paired with
+    transforms: Vec<(Pass, Arc<dyn CallTransform>)>,
 }
 
 impl PalletCallConfig {
+    /// Create a new config with the given options
+    pub fn from_options(options: CallOptions) -> Self {
+        Self {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Load the options from a `.toml` or `.json` file
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use substrate_pallet_call_extract::PalletCallConfig;
+    /// let expanded = PalletCallConfig::from_path("call-extract.toml")
+    ///     .unwrap()
+    ///     .parse_pallet("pallet-balances")
+    ///     .unwrap();
+    /// ```
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        Ok(Self::from_options(CallOptions::from_path(path)?))
+    }
+
+    /// The serializable options of this config
+    pub fn options(&self) -> &CallOptions {
+        &self.options
+    }
+
+    /// Mutable access to the serializable options of this config
+    pub fn options_mut(&mut self) -> &mut CallOptions {
+        &mut self.options
+    }
+
     /// Set the name of the generated `Call` enum explicitly
     pub fn name(mut self, name: impl Into<String>) -> Self {
-        self.name = Some(name.into());
+        self.options.name = Some(name.into());
+        self
+    }
+
+    /// Prepend a comment with the tool version, the pallet, the hash of the
+    /// input and the options to the generated source, see
+    /// [`PROVENANCE_HEADER`]
+    pub fn provenance_header(self) -> Self {
+        self.header(PROVENANCE_HEADER)
+    }
+
+    /// Prepend a comment rendered from this template to the generated source,
+    /// see [`PROVENANCE_HEADER`] for the placeholders
+    pub fn header(mut self, template: impl Into<String>) -> Self {
+        self.options.header = Some(template.into());
+        self
+    }
+
+    /// Prepend this block verbatim to every written file, like a license
+    /// header or `#![allow(clippy::all)]`
+    pub fn preamble(mut self, preamble: impl Into<String>) -> Self {
+        self.options.preamble = Some(preamble.into());
+        self
+    }
+
+    /// Format every written file with the system `rustfmt`, so the generated
+    /// code matches the formatting of the repository
+    pub fn rustfmt(mut self, rustfmt: RustfmtOptions) -> Self {
+        self.options.rustfmt = Some(rustfmt);
         self
     }
 
     /// Set the conversion function to use when determine the variant names
     pub fn variant_name<F>(mut self, convert: F) -> Self
     where
-        F: Fn(&str) -> String + 'static,
+        F: Fn(&str) -> String + Send + Sync + 'static,
     {
-        self.variant_name_conversion = Some(Box::new(convert));
+        self.variant_name_conversion = Some(Arc::new(convert));
         self
     }
 
     /// Set the conversion function to use when determine the variant names
     pub fn generic_name<F>(mut self, convert: F) -> Self
     where
-        F: Fn(&TypePath) -> String + 'static,
+        F: Fn(&TypePath) -> String + Send + Sync + 'static,
     {
-        self.generic_name_conversion = Some(Box::new(convert));
+        self.generic_name_conversion = Some(Arc::new(convert));
         self
     }
 
-    /// Keep original comments, otherwise they're stripped
-    pub fn keep_comments<F>(mut self) -> Self {
-        self.keep_comments = true;
+    /// Set a function that replaces the type of a field before generics are
+    /// erased, if it returns `Some`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use substrate_pallet_call_extract::PalletCallConfig;
+    /// let config = PalletCallConfig::default().rewrite_type(|ty| {
+    ///     (ty == &syn::parse_quote!(Vec<u8>)).then(|| syn::parse_quote!(Bytes))
+    /// });
+    /// ```
+    pub fn rewrite_type<F>(mut self, rewrite: F) -> Self
+    where
+        F: Fn(&Type) -> Option<Type> + Send + Sync + 'static,
+    {
+        self.type_rewrite = Some(Arc::new(rewrite));
         self
     }
 
-    /// The name of the `codec` where the `Encode` `Decode` derive macros should
-    /// be accessed from This should the crate name as defined in
-    /// `Cargo.toml`, for example `codec` for: `codec = { package =
-    /// 'parity-scale-codec', version = '2.0.0', features = ['derive']}`
-    pub fn codec_crate(mut self, codec: impl Into<String>) -> Self {
-        self.codec_crate = Some(codec.into());
+    /// Set the function that determines the attributes of each generated field
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use substrate_pallet_call_extract::PalletCallConfig;
+    /// // strip all `#[codec(compact)]` attributes
+    /// let expanded = PalletCallConfig::default()
+    ///     .field_attributes(|field, _| {
+    ///         field
+    ///             .attrs
+    ///             .iter()
+    ///             .filter(|attr| !attr.path.is_ident("codec"))
+    ///             .cloned()
+    ///             .collect()
+    ///     })
+    ///     .parse_pallet("pallet-balances")
+    ///     .unwrap();
+    /// ```
+    pub fn field_attributes<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Field, &VariantInfo) -> Vec<Attribute> + Send + Sync + 'static,
+    {
+        self.field_attributes = Some(Arc::new(f));
         self
     }
 
-    /// The path to the module where the `RuntimeDebug` trait can be found
-    /// Such as `frame_support`
-    pub fn use_runtime_debug_from_crate(mut self, debug: impl Into<String>) -> Self {
-        self.runtime_debug = Some(debug.into());
+    /// Set the function that determines additional attributes for each
+    /// generated variant
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use substrate_pallet_call_extract::PalletCallConfig;
+    /// // feature gate all privileged calls
+    /// let expanded = PalletCallConfig::default()
+    ///     .variant_attributes(|info| {
+    ///         if info.call_name.starts_with("force_") {
+    ///             vec![syn::parse_quote!(#[cfg(feature = "governance")])]
+    ///         } else {
+    ///             Vec::new()
+    ///         }
+    ///     })
+    ///     .parse_pallet("pallet-balances")
+    ///     .unwrap();
+    /// ```
+    pub fn variant_attributes<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&VariantInfo) -> Vec<Attribute> + Send + Sync + 'static,
+    {
+        self.variant_attributes = Some(Arc::new(f));
         self
     }
 
-    /// Derive `frame_support::RuntimeDebug`
-    pub fn frame_support_runtime_debug(self) -> Self {
-        self.use_runtime_debug_from_crate("frame_support")
-    }
-
-    /// Push an additional derive such as "Debug" to add to the `Call`
-    pub fn push_derive_str(&mut self, attr: impl AsRef<str>) -> syn::Result<&mut Self> {
-        let derive = syn::parse_str(attr.as_ref())?;
-        self.additional_derives.push(derive);
-        Ok(self)
-    }
-
-    /// Push an additional derive such as "Debug" to add to the `Call`
-    pub fn push_derive(mut self, derive: Path) -> Self {
-        self.additional_derives.push(derive);
+    /// Set the function that determines the doc comments of each generated
+    /// variant from the lines of the original doc comment
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use substrate_pallet_call_extract::PalletCallConfig;
+    /// // only keep the summary and drop the weight tables
+    /// let expanded = PalletCallConfig::default()
+    ///     .docs(|lines, _| {
+    ///         lines
+    ///             .iter()
+    ///             .take_while(|line| !line.trim().is_empty())
+    ///             .cloned()
+    ///             .collect()
+    ///     })
+    ///     .parse_pallet("pallet-balances")
+    ///     .unwrap();
+    /// ```
+    pub fn docs<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&[String], &VariantInfo) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.docs = Some(Arc::new(f));
         self
     }
 
-    /// Push an additional attribute to add to the `Call`
-    pub fn push_attr(mut self, attr: Attribute) -> Self {
-        self.additional_attr.push(attr);
+    /// Run the transform after the given built-in pass
+    ///
+    /// Transforms that are added for the same pass run in the order they're
+    /// added. See [`CallTransform`] for an example.
+    pub fn transform_after<T>(mut self, pass: Pass, transform: T) -> Self
+    where
+        T: CallTransform + 'static,
+    {
+        self.transforms.push((pass, Arc::new(transform)));
         self
     }
 
-    /// Parse the previously extracted `pallet::Call` ast
-    pub fn parse(self, content: impl AsRef<str>) -> syn::Result<PalletCall> {
-        let input = syn::parse_str::<syn::DeriveInput>(content.as_ref())?;
-        Ok(PalletCall {
-            config: self,
-            input,
-        })
+    /// Run the transform after all built-in passes
+    pub fn transform<T>(self, transform: T) -> Self
+    where
+        T: CallTransform + 'static,
+    {
+        self.transform_after(Pass::Derives, transform)
     }
 
-    /// Expands given pallet first then `parse` the expanded `pallet::Call`
-    ///
-    /// This requires that the given pallet is added to the `Cargo.toml` as
-    /// dependency or dev-dependency. Also the given name must match the
-    /// crate name as defined in  `Cargo.toml`
+    /// Set the function that modifies the generated enum and its companion
+    /// items, before they're wrapped in a module
     ///
     /// # Example
     ///
-    /// This will expand the `pallet-balances` pallet and will parse the
-    /// `pallet::Call` enum the is generated by the substrate macros.
-    ///
     /// ```no_run
     /// # use substrate_pallet_call_extract::PalletCallConfig;
-    /// let expanded = PalletCallConfig::default().parse_pallet("pallet-balances").unwrap();
+    /// # use quote::quote;
+    /// // add a marker trait impl
+    /// let expanded = PalletCallConfig::default()
+    ///     .post_process(|tokens| {
+    ///         quote! {
+    ///             #tokens
+    ///             impl crate::Marker for Call {}
+    ///         }
+    ///     })
+    ///     .parse_pallet("pallet-balances")
+    ///     .unwrap();
     /// ```
-    pub fn parse_pallet(self, pallet_name: impl AsRef<str>) -> anyhow::Result<PalletCall> {
-        let expander = Expander::default();
-        let content = expander.expand_path(pallet_name, "pallet::Call".parse()?)?;
-        Ok(self.parse(content)?)
+    pub fn post_process<F>(mut self, f: F) -> Self
+    where
+        F: Fn(TokenStream) -> TokenStream + Send + Sync + 'static,
+    {
+        self.post_process = Some(Arc::new(f));
+        self
     }
-}
 
-/// Represents a `pallet::Call` that can be turned into a generic `Call` enum
-/// instead of being bound to `T:Config`
-pub struct PalletCall {
-    /// Parameters for how to modify expansion
-    config: PalletCallConfig,
-    /// The parsed `Call` ast
-    pub input: syn::DeriveInput,
-}
+    /// Add the signature of the original dispatchable to the docs of each
+    /// variant, like `origin call: fn transfer(origin, dest: ..)`
+    pub fn signature_docs(mut self) -> Self {
+        self.options.signature_docs = true;
+        self
+    }
 
-impl PalletCall {
-    /// Expands the pallet call as configured in the `PalletCallConfig`
-    ///
-    /// The returned `TokenStream` will be a call enum in which any unique type
-    /// bound to the `T:Config` trait of `pallet::Call` (like `T::Balance`) will
-    /// be replaced by a generic type
-    ///
-    /// Example
+    /// Set how `#[cfg(..)]` and `#[cfg_attr(..)]` attributes on variants and
+    /// fields are handled, by default they're kept
+    pub fn cfg_policy(mut self, policy: CfgPolicy) -> Self {
+        self.options.cfg = policy;
+        self
+    }
+
+    /// Only keep attributes with the given paths, like `codec` or `allow`
     ///
-    /// Let this be an excerpt of the generated `pallet::Call`
-    /// ```ignore
-    /// pub enum Call<T: Config> {
-    ///     set_balance(
-    ///         <T::Lookup as StaticLookup>::Source,
-    ///         #[codec(compact)] T::Balance,
-    ///         #[codec(compact)] T::Balance,
-    ///     ),
-    /// }
-    /// ```
+    /// Doc comments are not affected by this, see `keep_comments`.
+    pub fn allow_attributes<I, S>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options
+            .allow_attributes
+            .extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Remove all attributes with the given paths, like `rustfmt::skip`
+    pub fn deny_attributes<I, S>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options
+            .deny_attributes
+            .extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Emit calls without parameters as unit variants, like `Remark`, instead
+    /// of `Remark()`
     ///
-    /// calling `expand` will return the corresponding generic enum:
+    /// Both encode identically.
+    pub fn unit_variants(mut self) -> Self {
+        self.options.unit_variants = true;
+        self
+    }
+
+    /// Set how fields marked with `#[codec(compact)]` are emitted
+    pub fn compact_mode(mut self, mode: CompactMode) -> Self {
+        self.options.compact = mode;
+        self
+    }
+
+    /// Set how references to types of the `T: Config`, like `&T::AccountId`,
+    /// are emitted
+    pub fn reference_mode(mut self, mode: ReferenceMode) -> Self {
+        self.options.references = mode;
+        self
+    }
+
+    /// Set the visibility of the generated enum and its companion items, like
+    /// `pub(crate)` or an empty string for private items
     ///
-    /// ```ignore
-    /// pub enum Call<Source, Balance> {
-    ///     SetBalance(Source, #[codec(compact)] Balance, #[codec(compact)] Balance),
-    /// }
-    /// ```
-    pub fn expand(&self) -> syn::Result<TokenStream> {
-        let structure = synstructure::Structure::new(&self.input);
+    /// If the output is wrapped in a module, the module itself stays `pub`.
+    pub fn visibility(mut self, vis: impl Into<String>) -> Self {
+        self.options.visibility = Some(vis.into());
+        self
+    }
 
-        // the name of the final call enum
-        let name = self.config.name.as_deref().unwrap_or("Call");
-        let name = syn::parse_str::<Ident>(name)?;
+    /// Emit the call indices as explicit discriminants of a `#[repr(u8)]` enum
+    /// with a fieldless `<Name>Name` companion enum, requires Rust 1.66
+    pub fn explicit_indices(mut self) -> Self {
+        self.options.explicit_indices = true;
+        self
+    }
 
-        // the codec crate to use
-        let codec_crate = self.config.codec_crate.as_deref().unwrap_or("codec");
-        let codec_crate = syn::parse_str::<Ident>(codec_crate)?;
+    /// Fail the expansion if any information of the `pallet::Call` would be
+    /// dropped, see `PalletCall::expand_with_report`
+    pub fn deny_warnings(mut self) -> Self {
+        self.options.deny_warnings = true;
+        self
+    }
 
-        let runtime_dbg = self
-            .config
-            .runtime_debug
-            .as_ref()
-            .map(|s| syn::parse_str::<Path>(&format!("{}::RuntimeDebug", s)))
-            .transpose()?
-            .map(|p| quote! { #[derive(#p)]})
-            .unwrap_or_else(|| quote! {});
-
-        // all unique `Config` trait generics used for call parameters
-        let mut generics = BTreeMap::new();
-        let mut variants = Vec::with_capacity(structure.variants().len());
-
-        for variant in structure.variants().iter().skip_while(|v| {
-            let ast = v.ast();
-            // skip the `__ignore` variant, which is also marked `[codec(skip)]`
-            ast.ident.to_string().to_lowercase() == "__ignore"
-        }) {
-            let ast = variant.ast();
-
-            let variant_name = self
-                .config
-                .variant_name_conversion
-                .as_ref()
-                .map(|c| (c)(&ast.ident.to_string()))
-                .unwrap_or_else(|| ast.ident.to_string().to_pascal_case());
-            let variant_name = syn::parse_str::<Ident>(&variant_name)?;
-
-            let mut fields = Vec::with_capacity(variant.bindings().len());
-
-            for binding in variant.bindings() {
-                let mut field = binding.ast().clone();
-                if let Type::Path(ref mut path) = field.ty {
-                    if !binding.referenced_ty_params().is_empty() {
-                        // generic type
-                        let ty_str = quote!(#path).to_string();
-                        let generic_ty = generics.entry(ty_str).or_insert_with(|| {
-                            self.config
-                                .generic_name_conversion
-                                .as_ref()
-                                .map(|c| (c)(path))
-                                .unwrap_or_else(|| {
-                                    let ty = path.path.segments.last().unwrap();
-                                    quote!(#ty).to_string()
-                                })
-                        });
-                        // create a new field with the generic as type
-                        let ident = syn::parse_str::<Ident>(generic_ty)?;
-                        let mut segments = Punctuated::new();
-                        segments.push(PathSegment::from(ident));
-                        *path = TypePath {
-                            qself: None,
-                            path: Path {
-                                leading_colon: None,
-                                segments,
-                            },
-                        };
-                    }
-                } else {
-                    return Err(syn::Error::new(
-                        field.span(),
-                        "Only TypePaths are supported currently",
-                    ));
-                }
-                fields.push(field);
-            }
+    /// Use this type for fields with unsupported types, like `[T::Hash; 32]`,
+    /// instead of failing the expansion
+    pub fn opaque_type(mut self, ty: Type) -> Self {
+        self.options.opaque_type = Some(quote!(#ty).to_string());
+        self
+    }
 
-            // parse as fields unnamed
-            // TODO support named fields as well
-            let fields = Fields::Unnamed(syn::parse_str::<FieldsUnnamed>(
-                &quote! {( #(#fields ),* )}.to_string(),
-            )?);
+    /// Skip calls that can't be expanded, like calls with unsupported
+    /// parameter types, instead of failing the expansion
+    ///
+    /// Every skipped call is reported as a `WarningKind::InvalidCall`
+    /// warning, see `PalletCall::expand_with_report`.
+    pub fn lenient(mut self) -> Self {
+        self.options.lenient = true;
+        self
+    }
 
-            let mut attrs = ast.attrs.to_vec();
-            if !self.config.keep_comments {
-                remove_doc_attributes(&mut attrs);
-            }
+    /// Validate the generated enum before it's emitted and fail with all
+    /// findings of the validation, see `PalletCall::validate`
+    pub fn strict(mut self) -> Self {
+        self.options.strict = true;
+        self
+    }
 
-            variants.push(Variant {
-                attrs,
-                ident: variant_name,
-                fields,
-                discriminant: ast.discriminant.clone(),
-            });
-        }
+    /// Emit a `<NAME>_HASHES` constant, like `CALL_HASHES`, with the
+    /// `blake2_256` hash of `<pallet>::<call>`, like `Balances::transfer`, for
+    /// every call
+    pub fn call_hashes(mut self, pallet: impl Into<String>) -> Self {
+        self.options.call_hashes = Some(pallet.into());
+        self
+    }
 
-        let mut call_enum_attrs = structure.ast().attrs.clone();
-        if !self.config.keep_comments {
-            remove_doc_attributes(&mut call_enum_attrs);
-        }
+    /// Emit a `<NAME>_CALLS` table of `<Name>Meta`, like `CALL_CALLS`, that
+    /// describes every call with its name, index, parameters and doc summary
+    pub fn calls_table(mut self) -> Self {
+        self.options.calls_table = true;
+        self
+    }
 
-        let generics = if generics.is_empty() {
-            quote! {}
-        } else {
-            let generics = generics
-                .values()
-                .map(|gen| syn::parse_str::<Ident>(gen))
-                .collect::<Result<Vec<_>, _>>()?;
-            quote! {< #( #generics), * > }
-        };
+    /// Emit an `encoded_size_hint` method that returns the estimated size of
+    /// the encoded call, based on `Encode::size_hint`
+    pub fn size_hint(mut self) -> Self {
+        self.options.size_hint = true;
+        self
+    }
 
-        let additional_attr = &self.config.additional_attr;
-        let additional_derives = &self.config.additional_derives;
-        let call_enum = quote! {
-            #[derive(
-                Clone, PartialEq, Eq,
-                #codec_crate::Encode,
-                #codec_crate::Decode,
-                #( #additional_derives ), *
-            )]
-            #runtime_dbg
-            #(#additional_attr)*
-            pub enum #name # generics {
-                #( #variants ),*
-            }
-        };
+    /// Emit a `<Name>Filter` type that matches the calls of the allow list,
+    /// or all if it's empty, that are not in the deny list
+    pub fn call_filter<A, D, S, T>(mut self, allow: A, deny: D) -> Self
+    where
+        A: IntoIterator<Item = S>,
+        D: IntoIterator<Item = T>,
+        S: Into<String>,
+        T: Into<String>,
+    {
+        let filter = self
+            .options
+            .call_filter
+            .get_or_insert_with(Default::default);
+        filter.allow = allow.into_iter().map(Into::into).collect();
+        filter.deny = deny.into_iter().map(Into::into).collect();
+        self
+    }
 
-        Ok(call_enum)
+    /// Implement `frame_support::traits::Contains` for the `call_filter`,
+    /// behind the given feature of the consuming crate like `frame-support`
+    pub fn call_filter_contains(mut self, feature: impl Into<String>) -> Self {
+        let filter = self
+            .options
+            .call_filter
+            .get_or_insert_with(Default::default);
+        filter.contains_feature = Some(feature.into());
+        self
     }
-}
 
-fn remove_doc_attributes(attrs: &mut Vec<Attribute>) {
-    attrs.retain(|attr| !attr.path.is_ident("doc"));
-}
+    /// Emit a `for_each_<name>!` macro, like `for_each_call!`, that expands a
+    /// block for every call, it's `#[macro_export]`ed if the items are `pub`
+    pub fn for_each_call(mut self) -> Self {
+        self.options.for_each_call = true;
+        self
+    }
 
-/// How to expand the call parameters as enum variant fields
-pub enum ParameterStyle {
-    /// Use default `(ty,ty)` unnamed fields
-    Unnamed,
-    /// Expand call parameters as named fields
-    // TODO add convert type for determine the name, allow extracting it from the ast of the actual
-    // function fn(call_name, index)
-    Named(Option<Box<dyn Fn(&str) -> String>>),
-}
+    /// Classify the origin every call requires from the un-expanded source of
+    /// the pallet, like `pallets/balances/src/lib.rs`
+    ///
+    /// The origins are part of the `metadata`, see [`call_origins`] for how
+    /// they're detected.
+    pub fn origin_source(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.options.origin_source = Some(path.into());
+        self
+    }
 
-impl Default for ParameterStyle {
-    fn default() -> Self {
-        ParameterStyle::Unnamed
+    /// Keep the docs of the calls on the generated variants and document
+    /// named fields with the argument docs of their call
+    ///
+    /// Arguments are documented by convention as list items in the docs of
+    /// the dispatchable, like ``- `dest`: The recipient``. Fields are only
+    /// named with `ParameterStyle::Named`.
+    pub fn call_docs(mut self) -> Self {
+        self.options.call_docs = true;
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Take the docs of the calls from the dispatchables in the un-expanded
+    /// source of the pallet, like `pallets/balances/src/lib.rs`, see
+    /// `call_docs`
+    pub fn docs_source(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.options.docs_source = Some(path.into());
+        self
+    }
 
-    #[test]
-    fn can_extract_balances() {
-        let expanded = PalletCallConfig::default()
-            .push_derive(syn::parse_str("std::fmt::Debug").unwrap())
-            .parse_pallet("pallet-balances")
-            .unwrap()
-            .expand()
-            .unwrap();
+    /// Emit a `<Name>Origin` enum, like `CallOrigin`, and a `required_origin`
+    /// method that returns the origin a call requires, this requires the
+    /// `origin_source`
+    pub fn required_origin(mut self) -> Self {
+        self.options.required_origin = true;
+        self
+    }
 
-        let expected = quote! {
-            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode, std::fmt::Debug)]
-            pub enum Call<Source, Balance> {
-                Transfer(Source, #[codec(compact)] Balance),
-                SetBalance(Source, #[codec(compact)] Balance, #[codec(compact)] Balance),
-                ForceTransfer(Source, Source, #[codec(compact)] Balance),
-                TransferKeepAlive(Source, #[codec(compact)] Balance)
-            }
-        };
+    /// Attach the benchmarked weight of every call from the pallet's
+    /// `weights.rs`, like `pallets/balances/src/weights.rs`
+    ///
+    /// The weights are part of the `metadata`, see [`parse_weights`] for what
+    /// is parsed.
+    pub fn weights_source(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.options.weights_source = Some(path.into());
+        self
+    }
+
+    /// Emit a `<Name>Weight` struct, like `CallWeight`, and a `weight` method
+    /// that returns the benchmarked weight of a call, this requires the
+    /// `weights_source`
+    pub fn static_weights(mut self) -> Self {
+        self.options.static_weights = true;
+        self
+    }
 
+    /// Emit a test for every sample of hex encoded call data in the `.json`
+    /// or `.toml` file, which asserts that it decodes to the variant of the
+    /// call with the `concrete` enum, like `{ "remark": "0x020c010203" }`
+    pub fn decode_fixtures(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.options.decode_fixtures = Some(path.into());
+        self
+    }
+
+    /// Emit an example of every call with the `concrete` enum and its SCALE
+    /// encoding, as test vectors for other implementations
+    pub fn encoded_examples(mut self) -> Self {
+        self.options.encoded_examples = true;
+        self
+    }
+
+    /// Use these argument expressions for the example of the call, see
+    /// `encoded_examples`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use substrate_pallet_call_extract::PalletCallConfig;
+    /// let config = PalletCallConfig::default()
+    ///     .encoded_examples()
+    ///     .example_args("remark", vec![syn::parse_quote!(b"hello".to_vec())]);
+    /// ```
+    pub fn example_args<I>(mut self, call: impl Into<String>, args: I) -> Self
+    where
+        I: IntoIterator<Item = Expr>,
+    {
+        self.options.example_args.insert(
+            call.into(),
+            args.into_iter()
+                .map(|arg| quote!(#arg).to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Emit a `<Name>Types` trait with an associated type for every generic,
+    /// like `CallTypes`, and a `<Name>For<C>` alias of the enum with the
+    /// types of `C`
+    pub fn types_trait(mut self) -> Self {
+        self.options.types_trait = true;
+        self
+    }
+
+    /// Emit a constructor function for every call that is generic over the
+    /// `<Name>Types` trait, which is emitted as well
+    pub fn trait_constructors(mut self) -> Self {
+        self.options.trait_constructors = true;
+        self
+    }
+
+    /// Document every constructor of `trait_constructors` with an example
+    /// that builds the call with placeholder values and encodes it
+    pub fn constructor_examples(mut self) -> Self {
+        self.options.constructor_examples = true;
+        self
+    }
+
+    /// Emit `to_value` and `from_value` methods that convert the generated
+    /// enum to and from a dynamic `scale_value::Value`
+    pub fn scale_value(mut self) -> Self {
+        self.options.scale_value = true;
+        self
+    }
+
+    /// Keep the `T: Config` generic of the `pallet::Call` instead of
+    /// introducing a generic for every type that refers to it, with a
+    /// `<Generic>Of<T>` alias for every such type
+    pub fn keep_config_generics(mut self) -> Self {
+        self.options.keep_generics = true;
+        self
+    }
+
+    /// Emit a `<call>_with_accounts` constructor for every call with a lookup
+    /// source parameter, that takes a `sp_runtime::AccountId32` instead
+    pub fn lookup_helpers(mut self) -> Self {
+        self.options.lookup_helpers = true;
+        self
+    }
+
+    /// Keep the `__Ignore` variant of the `pallet::Call`, which is skipped by
+    /// the codec
+    pub fn keep_skipped_variants(mut self) -> Self {
+        self.options.keep_skipped_variants = true;
+        self
+    }
+
+    /// Also emit a concrete enum with this name, in which all generics are
+    /// replaced by the given types
+    ///
+    /// `From` conversions between the generic and the concrete enum are
+    /// generated as well, so that the generic enum can be used by libraries
+    /// while applications work with the concrete one.
+    pub fn concrete<I, S>(mut self, name: impl Into<String>, types: I) -> Self
+    where
+        I: IntoIterator<Item = (S, Type)>,
+        S: Into<String>,
+    {
+        self.options.concrete = Some(ConcreteCall {
+            name: name.into(),
+            types: types
+                .into_iter()
+                .map(|(generic, ty)| (generic.into(), type_to_string(&ty)))
+                .collect(),
+        });
+        self
+    }
+
+    /// Emit a `pub type <generic> = <ty>;` alias in a `types` module
+    pub fn type_alias(mut self, generic: impl Into<String>, ty: Type) -> Self {
+        self.options
+            .aliases
+            .insert(generic.into(), quote!(#ty).to_string());
+        self
+    }
+
+    /// Mark the generated enum `#[doc(hidden)]`, for crates that include the
+    /// generated file but don't want it in their rustdoc
+    pub fn doc_hidden(mut self) -> Self {
+        self.options.doc_hidden = true;
+        self
+    }
+
+    /// Mark the variants of the calls with the given names `#[doc(hidden)]`
+    ///
+    /// Names can be glob patterns like `force_*`.
+    pub fn hidden_calls<I, S>(mut self, calls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options
+            .hidden_calls
+            .extend(calls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Emit `#[allow(missing_docs)]` on the generated enum, so that calls
+    /// without docs don't trigger the `missing_docs` lint of the including
+    /// crate
+    pub fn allow_missing_docs(mut self) -> Self {
+        self.options.allow_missing_docs = true;
+        self
+    }
+
+    /// Mark the generated enum `#[non_exhaustive]`, so that new calls can be
+    /// added without breaking downstream matches
+    pub fn non_exhaustive(mut self) -> Self {
+        self.options.non_exhaustive = true;
+        self
+    }
+
+    /// Keep original comments, otherwise they're stripped
+    pub fn keep_comments<F>(mut self) -> Self {
+        self.options.keep_comments = true;
+        self
+    }
+
+    /// The name of the `codec` where the `Encode` `Decode` derive macros should
+    /// be accessed from This should the crate name as defined in
+    /// `Cargo.toml`, for example `codec` for: `codec = { package =
+    /// 'parity-scale-codec', version = '2.0.0', features = ['derive']}` or a
+    /// path like `sp_api::codec`
+    pub fn codec_crate(mut self, codec: impl Into<String>) -> Self {
+        self.options.codec_crate = Some(codec.into());
+        self
+    }
+
+    /// The path to the module where the `RuntimeDebug` trait can be found
+    /// Such as `frame_support`
+    pub fn use_runtime_debug_from_crate(mut self, debug: impl Into<String>) -> Self {
+        self.options.runtime_debug = Some(debug.into());
+        self
+    }
+
+    /// Derive `frame_support::RuntimeDebug`
+    pub fn frame_support_runtime_debug(self) -> Self {
+        self.use_runtime_debug_from_crate("frame_support")
+    }
+
+    /// Push an additional derive such as "Debug" to add to the `Call`
+    pub fn push_derive_str(&mut self, attr: impl AsRef<str>) -> Result<&mut Self> {
+        let derive = options::parse_config("derive", attr.as_ref(), syn::parse_str::<Path>)?;
+        self.options.derives.push(quote!(#derive).to_string());
+        Ok(self)
+    }
+
+    /// Replace the base derives `Clone, PartialEq, Eq, Encode, Decode` of the
+    /// `Call`, derives added with `push_derive` are still emitted
+    pub fn base_derives<I>(mut self, derives: I) -> Self
+    where
+        I: IntoIterator<Item = Path>,
+    {
+        self.options.base_derives = Some(
+            derives
+                .into_iter()
+                .map(|derive| quote!(#derive).to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Omit the `Encode` and `Decode` derives and all `#[codec(..)]`
+    /// attributes, for consumers that only need the shape of the enum, like
+    /// serde based JSON APIs that don't depend on `parity-scale-codec`
+    pub fn without_codec(mut self) -> Self {
+        self.options.without_codec = true;
+        self
+    }
+
+    /// Also derive `DecodeWithMemTracking` of the codec crate, which recent
+    /// runtimes require for their calls
+    pub fn decode_with_mem_tracking(mut self) -> Self {
+        self.options.decode_with_mem_tracking = true;
+        self
+    }
+
+    /// Also derive `MaxEncodedLen` of the codec crate, which requires bounded
+    /// field types
+    pub fn max_encoded_len(mut self) -> Self {
+        self.options.max_encoded_len = true;
+        self
+    }
+
+    /// Emit `#[codec(encode_bound(..))]` with the given predicates, which
+    /// replaces the bounds the `Encode` derive infers for the generics
+    pub fn encode_bound<I>(mut self, predicates: I) -> Self
+    where
+        I: IntoIterator<Item = WherePredicate>,
+    {
+        self.options.encode_bound = Some(predicates_to_strings(predicates));
+        self
+    }
+
+    /// Emit `#[codec(decode_bound(..))]` with the given predicates, which
+    /// replaces the bounds the `Decode` derive infers for the generics
+    pub fn decode_bound<I>(mut self, predicates: I) -> Self
+    where
+        I: IntoIterator<Item = WherePredicate>,
+    {
+        self.options.decode_bound = Some(predicates_to_strings(predicates));
+        self
+    }
+
+    /// Emit `#[codec(dumb_trait_bound)]`, so the codec derives bound every
+    /// generic instead of the types of the fields
+    pub fn dumb_trait_bound(mut self) -> Self {
+        self.options.dumb_trait_bound = true;
+        self
+    }
+
+    /// Push an additional derive such as "Debug" to add to the `Call`
+    pub fn push_derive(mut self, derive: Path) -> Self {
+        self.options.derives.push(quote!(#derive).to_string());
+        self
+    }
+
+    /// Apply the derives and attributes of a profile, like `Profile::Indexer`
+    ///
+    /// The profile only adds options, so they can still be changed
+    /// afterwards, like replacing the base derives.
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.options.apply_profile(profile);
+        self
+    }
+
+    /// Push an additional derive that requires `std`, like `serde::Serialize`
+    ///
+    /// The derive is only emitted with the `std` feature:
+    /// `#[cfg_attr(feature = "std", derive(..))]`
+    pub fn push_std_derive(mut self, derive: Path) -> Self {
+        self.options.std_derives.push(quote!(#derive).to_string());
+        self
+    }
+
+    /// Generate code that only depends on `core` and `alloc`
+    pub fn no_std(mut self) -> Self {
+        self.options.no_std = true;
+        self
+    }
+
+    /// Push an additional attribute to add to the `Call`
+    pub fn push_attr(mut self, attr: Attribute) -> Self {
+        self.options.attributes.push(quote!(#attr).to_string());
+        self
+    }
+
+    /// Emit the generated code inside a `pub mod <name> { .. }` module
+    pub fn module(mut self, name: impl Into<String>) -> Self {
+        self.options.module = Some(name.into());
+        self
+    }
+
+    /// Push an additional `use` statement such as `use codec::{Encode,
+    /// Decode};` to emit before the generated `Call`
+    pub fn push_import_str(&mut self, import: impl AsRef<str>) -> Result<&mut Self> {
+        let import = options::parse_config("import", import.as_ref(), syn::parse_str::<ItemUse>)?;
+        self.options.imports.push(quote!(#import).to_string());
+        Ok(self)
+    }
+
+    /// Push an additional `use` statement to emit before the generated `Call`
+    ///
+    /// Together with `module` this makes the output compile standalone when
+    /// included via `include!`
+    pub fn push_import(mut self, import: ItemUse) -> Self {
+        self.options.imports.push(quote!(#import).to_string());
+        self
+    }
+
+    /// Only include the calls with the given names, like `transfer`, the
+    /// remaining calls keep their original index
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use substrate_pallet_call_extract::PalletCallConfig;
+    /// let expanded = PalletCallConfig::default()
+    ///     .include_calls(["transfer", "transfer_keep_alive"])
+    ///     .parse_pallet("pallet-balances")
+    ///     .unwrap();
+    /// ```
+    pub fn include_calls<I, S>(mut self, calls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options
+            .include_calls
+            .extend(calls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Exclude the calls with the given names from the generated `Call`
+    pub fn exclude_calls<I, S>(mut self, calls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options
+            .exclude_calls
+            .extend(calls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Override how the call with the given original name is expanded
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use substrate_pallet_call_extract::{CallOverride, PalletCallConfig};
+    /// let expanded = PalletCallConfig::default()
+    ///     .override_call(
+    ///         "set_balance",
+    ///         CallOverride {
+    ///             name: Some("ForceSetBalance".to_string()),
+    ///             ..Default::default()
+    ///         },
+    ///     )
+    ///     .parse_pallet("pallet-balances")
+    ///     .unwrap();
+    /// ```
+    pub fn override_call(mut self, call: impl Into<String>, call_override: CallOverride) -> Self {
+        self.options.overrides.insert(call.into(), call_override);
+        self
+    }
+
+    /// Use the concrete type for the generic with the given name instead of
+    /// introducing a generic parameter, e.g. `u128` for `Balance`
+    pub fn substitute(mut self, generic: impl Into<String>, ty: Type) -> Self {
+        self.options
+            .substitutions
+            .insert(generic.into(), quote!(#ty).to_string());
+        self
+    }
+
+    /// Parse the previously extracted `pallet::Call` ast
+    pub fn parse(self, content: impl AsRef<str>) -> Result<PalletCall<'static>> {
+        let input = syn::parse_str::<syn::DeriveInput>(content.as_ref())?;
+        Ok(PalletCall {
+            config: Cow::Owned(self),
+            input: Cow::Owned(input),
+        })
+    }
+
+    /// Parse the `pallet::Call` from the expanded source of a pallet crate,
+    /// as printed by `cargo expand`, or from the `Call` enum itself
+    pub fn parse_expanded(self, content: impl AsRef<str>) -> Result<PalletCall<'static>> {
+        let content = content.as_ref();
+        if let Ok(input) = syn::parse_str::<syn::DeriveInput>(content) {
+            return Ok(PalletCall {
+                config: Cow::Owned(self),
+                input: Cow::Owned(input),
+            });
+        }
+        let file = syn::parse_file(content)?;
+        let call = find_call(&file.items).ok_or_else(|| {
+            syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "No `pallet::Call` enum found",
+            )
+        })?;
+        Ok(self.parse_item_enum(call.clone()))
+    }
+
+    /// Use the already parsed `pallet::Call` item, for example pulled out of
+    /// a larger file, fails if the item is not an enum
+    pub fn parse_item(self, item: syn::Item) -> Result<PalletCall<'static>> {
+        match item {
+            syn::Item::Enum(item) => Ok(self.parse_item_enum(item)),
+            item => Err(syn::Error::new_spanned(item, "expected the `pallet::Call` enum").into()),
+        }
+    }
+
+    /// Use the already parsed `pallet::Call` enum
+    pub fn parse_item_enum(self, item: syn::ItemEnum) -> PalletCall<'static> {
+        PalletCall {
+            config: Cow::Owned(self),
+            input: Cow::Owned(item.into()),
+        }
+    }
+
+    /// Expands given pallet first then `parse` the expanded `pallet::Call`
+    ///
+    /// This requires that the given pallet is added to the `Cargo.toml` as
+    /// dependency or dev-dependency. Also the given name must match the
+    /// crate name as defined in  `Cargo.toml`
+    ///
+    /// # Example
+    ///
+    /// This will expand the `pallet-balances` pallet and will parse the
+    /// `pallet::Call` enum the is generated by the substrate macros.
+    ///
+    /// ```no_run
+    /// # use substrate_pallet_call_extract::PalletCallConfig;
+    /// let expanded = PalletCallConfig::default().parse_pallet("pallet-balances").unwrap();
+    /// ```
+    pub fn parse_pallet(self, pallet_name: impl AsRef<str>) -> anyhow::Result<PalletCall<'static>> {
+        let expander = Expander::default();
+        let content = expander.expand_path(pallet_name, "pallet::Call".parse()?)?;
+        Ok(self.parse(content)?)
+    }
+}
+
+impl From<CallOptions> for PalletCallConfig {
+    fn from(options: CallOptions) -> Self {
+        Self::from_options(options)
+    }
+}
+
+/// Represents a `pallet::Call` that can be turned into a generic `Call` enum
+/// instead of being bound to `T:Config`
+#[derive(Clone)]
+pub struct PalletCall<'a> {
+    /// Parameters for how to modify expansion
+    config: Cow<'a, PalletCallConfig>,
+    /// The parsed `Call` ast
+    pub input: Cow<'a, syn::DeriveInput>,
+}
+
+impl<'a> PalletCall<'a> {
+    /// Borrows an already parsed `pallet::Call` and the config
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use substrate_pallet_call_extract::{PalletCall, PalletCallConfig};
+    /// let input = syn::parse_str("pub enum Call<T: Config> { remark(Vec<u8>) }").unwrap();
+    /// let generic = PalletCallConfig::default();
+    /// let named = PalletCallConfig::default().name("SystemCall");
+    /// let generic = PalletCall::new(&input, &generic).expand().unwrap();
+    /// let named = PalletCall::new(&input, &named).expand().unwrap();
+    /// ```
+    pub fn new(input: &'a syn::DeriveInput, config: &'a PalletCallConfig) -> Self {
+        PalletCall {
+            config: Cow::Borrowed(config),
+            input: Cow::Borrowed(input),
+        }
+    }
+
+    /// Clones the borrowed ast and config, if any
+    pub fn into_owned(self) -> PalletCall<'static> {
+        PalletCall {
+            config: Cow::Owned(self.config.into_owned()),
+            input: Cow::Owned(self.input.into_owned()),
+        }
+    }
+
+    /// Expands the pallet call as configured in the `PalletCallConfig`
+    ///
+    /// The returned `TokenStream` will be a call enum in which any unique type
+    /// bound to the `T:Config` trait of `pallet::Call` (like `T::Balance`) will
+    /// be replaced by a generic type
+    ///
+    /// Example
+    ///
+    /// Let this be an excerpt of the generated `pallet::Call`
+    /// ```ignore
+    /// pub enum Call<T: Config> {
+    ///     set_balance(
+    ///         <T::Lookup as StaticLookup>::Source,
+    ///         #[codec(compact)] T::Balance,
+    ///         #[codec(compact)] T::Balance,
+    ///     ),
+    /// }
+    /// ```
+    ///
+    /// calling `expand` will return the corresponding generic enum:
+    ///
+    /// ```ignore
+    /// pub enum Call<Source, Balance> {
+    ///     SetBalance(Source, #[codec(compact)] Balance, #[codec(compact)] Balance),
+    /// }
+    /// ```
+    pub fn expand(&self) -> Result<TokenStream> {
+        self.expand_with_report().map(|(tokens, _)| tokens)
+    }
+
+    /// Expands the pallet call like `expand` and also returns warnings about
+    /// all information of the `pallet::Call` that was dropped, like doc
+    /// comments, filtered attributes or skipped calls
+    ///
+    /// If `deny_warnings` is set, the warnings are returned as errors instead.
+    pub fn expand_with_report(&self) -> Result<(TokenStream, Vec<Warning>)> {
+        let call = self.expand_call()?;
+        let call_enum = self.emit_enum(&call)?;
+        let tokens = self.wrap(self.config.options.module.as_deref(), call_enum)?;
+        Ok((tokens, call.warnings))
+    }
+
+    /// Returns all calls of the parsed `Call` without expanding it
+    ///
+    /// This includes all calls, regardless of `include_calls` and
+    /// `exclude_calls`, with their original types.
+    pub fn calls(&self) -> Result<Vec<CallInfo>> {
+        let call = ExpandedCall::new(&self.input, &self.config.options)?;
+        Ok(call.variants.iter().map(CallInfo::new).collect())
+    }
+
+    /// Returns the metadata of the expanded call: the included calls and the
+    /// original types of all introduced generics
+    pub fn metadata(&self) -> Result<CallMetadata> {
+        let call = self.expand_call()?;
+        let generics = call
+            .generics
+            .iter()
+            .map(|(ty, name)| {
+                (
+                    name.clone(),
+                    type_to_string(&Type::Path(call.types[ty].clone())),
+                )
+            })
+            .collect();
+        Ok(CallMetadata {
+            version: CallMetadata::VERSION,
+            name: call.name.to_string(),
+            generics,
+            calls: call.variants.iter().map(CallInfo::new).collect(),
+        })
+    }
+
+    /// Exports the `metadata` as pretty printed JSON
+    ///
+    /// The document is stable: its fields and the generics are sorted and the
+    /// calls keep the order of the `pallet::Call`.
+    pub fn to_json(&self) -> Result<String> {
+        let metadata = self.metadata()?;
+        Ok(serde_json::to_string_pretty(&metadata).expect("metadata is serializable"))
+    }
+
+    /// Returns the call ABI, the encoding relevant part of the `metadata`
+    pub fn abi(&self) -> Result<CallAbi> {
+        Ok(self.metadata()?.into())
+    }
+
+    /// Exports the `abi` as pretty printed JSON
+    pub fn to_abi_json(&self) -> Result<String> {
+        let abi = self.abi()?;
+        Ok(serde_json::to_string_pretty(&abi).expect("abi is serializable"))
+    }
+
+    /// Returns the locations of all generated variants and their fields in
+    /// the parsed `pallet::Call`, which was read from `file`
+    pub fn source_map(&self, file: Option<String>) -> Result<SourceMap> {
+        let call = self.expand_call()?;
+        Ok(SourceMap::new(&call, file))
+    }
+
+    /// Exports the `source_map` as pretty printed JSON, as a sidecar of the
+    /// generated code
+    pub fn to_source_map_json(&self, file: Option<String>) -> Result<String> {
+        let map = self.source_map(file)?;
+        Ok(serde_json::to_string_pretty(&map).expect("source map is serializable"))
+    }
+
+    /// Returns a JSON Schema of the serde representation of the generated enum
+    ///
+    /// This requires a substitution for every generic. Types that are not
+    /// known, like `sp_runtime::AccountId32`, accept any value.
+    pub fn json_schema(&self) -> Result<serde_json::Value> {
+        let call = self.expand_call()?;
+        if !call.generics.is_empty() {
+            let generics = call.generics.values().cloned().collect::<Vec<_>>();
+            return Err(Error::Config(format!(
+                "JSON Schema requires a substitution for `{}`",
+                generics.join("`, `")
+            )));
+        }
+        Ok(schema::call_schema(&call))
+    }
+
+    /// Returns TypeScript definitions of the serde representation of the
+    /// generated enum, as the content of a `.d.ts` file
+    pub fn to_typescript(&self) -> Result<String> {
+        let call = self.expand_call()?;
+        if !call.generics.is_empty() {
+            let generics = call.generics.values().cloned().collect::<Vec<_>>();
+            return Err(Error::Config(format!(
+                "TypeScript definitions require a substitution for `{}`",
+                generics.join("`, `")
+            )));
+        }
+        Ok(typescript::call_definitions(&call))
+    }
+
+    /// Renders a criterion benchmark of encoding and decoding every call, as
+    /// the content of a file in `benches/`, requires `encoded_examples`
+    pub fn to_bench_source(&self, module: &str) -> Result<String> {
+        let concrete = match self.config.options.concrete.as_ref() {
+            Some(concrete) if self.config.options.encoded_examples => concrete,
+            _ => {
+                return Err(Error::Config(
+                    "a benchmark requires a concrete enum with encoded examples".to_string(),
+                ))
+            }
+        };
+        let path = options::parse_config(
+            "bench module",
+            &format!("{}::{}", module, concrete.name),
+            syn::parse_str::<Path>,
+        )?;
+        Ok(output::render(bench::emit(&path))?)
+    }
+
+    /// Renders a compile-pass test that includes the generated file and
+    /// instantiates every call with dummy types for the generics
+    pub fn to_compile_test(&self, include: &str) -> Result<String> {
+        if self.config.options.keep_generics {
+            return Err(Error::Config(
+                "a compile test requires the generics to be erased".to_string(),
+            ));
+        }
+        let call = self.expand_call()?;
+        let test = compile_test::emit(&call, self.config.options.module.as_deref(), include)?;
+        Ok(output::render(test)?)
+    }
+
+    /// Runs `cargo check` over the generated code with the default
+    /// `Verifier`, which only depends on `codec`
+    #[cfg(feature = "verify")]
+    pub fn verify(&self) -> anyhow::Result<()> {
+        Verifier::default().verify(self)
+    }
+
+    /// Renders a markdown reference of all calls, with their index,
+    /// parameters and the summary of their docs
+    ///
+    /// The title is the configured name of the generated enum.
+    pub fn to_markdown(&self) -> Result<String> {
+        let title = self.config.options.name.as_deref().unwrap_or("Call");
+        Ok(markdown::render(title, &self.calls()?))
+    }
+
+    /// Checks that all generics are resolved, that variant names and codec
+    /// indices are unique, that indices fit in a `u8` and that all
+    /// identifiers and derives are valid
+    pub fn validate(&self) -> Vec<Finding> {
+        validate::validate(self)
+    }
+
+    /// Runs all passes over the parsed `Call`
+    ///
+    /// Each pass fails with the errors of all variants.
+    pub(crate) fn expand_call(&self) -> Result<ExpandedCall> {
+        if self.config.options.strict {
+            let findings = self.validate();
+            if !findings.is_empty() {
+                return Err(Error::Invalid(findings));
+            }
+        }
+        self.expand_unvalidated()
+    }
+
+    /// Runs all passes without the validation of `strict`
+    pub(crate) fn expand_unvalidated(&self) -> Result<ExpandedCall> {
+        let mut call = ExpandedCall::new(&self.input, &self.config.options)?;
+        if let Some(path) = self.config.options.origin_source.as_ref() {
+            let origins = origin::call_origins_from_path(path)?;
+            for v in call.variants.iter_mut() {
+                v.origin = origins.get(&v.call_name).cloned();
+            }
+        }
+        if let Some(path) = self.config.options.docs_source.as_ref() {
+            let docs = docs::call_docs_from_path(path)?;
+            for v in call.variants.iter_mut() {
+                if let Some(lines) = docs.get(&v.call_name) {
+                    for attrs in [&mut v.original.attrs, &mut v.variant.attrs] {
+                        attributes::remove_doc_attributes(attrs);
+                        attrs.splice(0..0, attributes::doc_attributes(lines));
+                    }
+                }
+            }
+        }
+        if let Some(path) = self.config.options.weights_source.as_ref() {
+            let weights = weights::parse_weights_from_path(path)?;
+            for v in call.variants.iter_mut() {
+                v.weight = weights.get(&v.call_name).copied();
+            }
+        }
+        for pass in Pass::ALL.iter().copied() {
+            pass.run(&self.config, &mut call)?;
+            for (after, transform) in &self.config.transforms {
+                if *after == pass {
+                    transform.transform(&self.config, &mut call)?;
+                }
+            }
+        }
+        if self.config.options.deny_warnings {
+            Error::combine(call.warnings.drain(..).map(Error::Warning))?;
+        }
+        Ok(call)
+    }
+
+    /// Emits the generic `Call` enum and applies the `post_process` function
+    pub(crate) fn emit_enum(&self, call: &ExpandedCall) -> Result<TokenStream> {
+        let tokens = self.emit_items(call)?;
+        Ok(match self.config.post_process.as_ref() {
+            Some(post_process) => (post_process)(tokens),
+            None => tokens,
+        })
+    }
+
+    /// Emits the generic `Call` enum with its attributes and the companion
+    /// items
+    fn emit_items(&self, call: &ExpandedCall) -> Result<TokenStream> {
+        let keep_generics = self.config.options.keep_generics;
+        let input_generics = call.declared_generics();
+        let lifetimes = &call.lifetimes;
+        let (generics, impl_generics, where_clause) = if keep_generics {
+            let (impl_generics, ty_generics, where_clause) = input_generics.split_for_impl();
+            (
+                quote! { #ty_generics },
+                quote! { #impl_generics },
+                quote! { #where_clause },
+            )
+        } else if call.generics.is_empty() && lifetimes.is_empty() {
+            (quote! {}, quote! {}, quote! {})
+        } else {
+            let generics = call
+                .generics
+                .values()
+                .map(|gen| parse_ident(gen))
+                .collect::<syn::Result<Vec<_>>>()?;
+            let params = lifetimes
+                .iter()
+                .map(|lifetime| quote! { #lifetime })
+                .chain(generics.iter().map(|generic| quote! { #generic }));
+            let generics = quote! {< #( #params), * > };
+            (generics.clone(), generics, quote! {})
+        };
+        let borrowed_error = |item: &str| {
+            Err(Error::Config(format!(
+                "{} can't be emitted for calls with borrowed fields",
+                item
+            )))
+        };
+
+        if self.config.options.without_codec {
+            let options = &self.config.options;
+            for (enabled, item) in [
+                (options.size_hint, "a size hint"),
+                (options.encoded_examples, "encoded examples"),
+                (options.decode_fixtures.is_some(), "decode fixtures"),
+            ] {
+                if enabled {
+                    return Err(Error::Config(format!(
+                        "{} can't be emitted without codec",
+                        item
+                    )));
+                }
+            }
+        }
+
+        let vis = self.visibility()?;
+        let codec_crate = self.config.options.parse_codec_crate()?;
+        let name = &call.name;
+        let explicit_indices = self.config.options.explicit_indices;
+        // calls are encoded by their position, so if calls were filtered out
+        // the remaining calls need their original index
+        let encoded = self
+            .config
+            .options
+            .parse_base_derives()?
+            .iter()
+            .any(|derive| {
+                derive
+                    .segments
+                    .last()
+                    .map(|s| s.ident == "Encode")
+                    .unwrap_or_default()
+            });
+        let index_attrs = !explicit_indices
+            && encoded
+            && call
+                .variants
+                .iter()
+                .enumerate()
+                .any(|(position, v)| usize::from(v.index) != position);
+        let variants = call
+            .variants
+            .iter()
+            .map(|v| {
+                let mut variant = v.variant.clone();
+                let index = Literal::u8_unsuffixed(v.index);
+                if explicit_indices {
+                    variant.discriminant = Some((Default::default(), syn::parse_quote!(#index)));
+                } else if index_attrs && attributes::explicit_index(&variant.attrs).is_none() {
+                    variant
+                        .attrs
+                        .push(syn::parse_quote!(#[codec(index = #index)]));
+                }
+                variant
+            })
+            .collect::<Vec<_>>();
+        let repr = if explicit_indices {
+            quote! { #[repr(u8)] }
+        } else {
+            quote! {}
+        };
+        // the variant that is skipped by the codec, with the generics of its
+        // `PhantomData`
+        let skipped = if keep_generics {
+            let params = call.input_generics.type_params().map(|param| &param.ident);
+            Some((format_ident!("__Ignore"), quote! { #( #params, )* }))
+        } else if self.config.options.keep_skipped_variants {
+            match call.skipped.as_ref() {
+                Some(ident) => {
+                    let params = call
+                        .generics
+                        .values()
+                        .map(|gen| parse_ident(gen))
+                        .collect::<syn::Result<Vec<_>>>()?;
+                    Some((ident.clone(), quote! { #( #params, )* }))
+                }
+                None => None,
+            }
+        } else {
+            None
+        };
+        let skipped_error = |item: &str| {
+            Err(Error::Config(format!(
+                "{} can't be emitted if the skipped variants are kept",
+                item
+            )))
+        };
+        let concrete = match self.config.options.concrete.as_ref() {
+            Some(_) if keep_generics => {
+                return Err(Error::Config(
+                    "a concrete enum can't be emitted if the `T: Config` generic is kept"
+                        .to_string(),
+                ))
+            }
+            Some(_) if skipped.is_some() => return skipped_error("a concrete enum"),
+            Some(_) if !lifetimes.is_empty() => return borrowed_error("a concrete enum"),
+            Some(concrete) => concrete::emit(call, concrete, &variants, &repr, &vis)?,
+            None => quote! {},
+        };
+        let decode_tests = match self.config.options.decode_fixtures.as_deref() {
+            Some(path) => {
+                let concrete = self.config.options.concrete.as_ref().ok_or_else(|| {
+                    Error::Config("decode fixtures require a concrete enum".to_string())
+                })?;
+                let concrete_name = parse_ident(&concrete.name)?;
+                let fixtures = fixtures::read_hex_fixtures(path)?;
+                fixtures::emit_decode_tests(call, &concrete_name, &codec_crate, &fixtures)?
+            }
+            None => quote! {},
+        };
+        let examples = match self.config.options.concrete.as_ref() {
+            Some(concrete) if self.config.options.encoded_examples => {
+                let concrete_name = parse_ident(&concrete.name)?;
+                fixtures::emit_examples(
+                    call,
+                    &concrete_name,
+                    &codec_crate,
+                    &self.config.options.example_args,
+                    &vis,
+                )?
+            }
+            None if self.config.options.encoded_examples => {
+                return Err(Error::Config(
+                    "encoded examples require a concrete enum".to_string(),
+                ))
+            }
+            _ => quote! {},
+        };
+        let options = &self.config.options;
+        let enum_generics = EnumGenerics {
+            ty: generics.clone(),
+            params: impl_generics.clone(),
+            where_clause: where_clause.clone(),
+        };
+        let call_hashes = match options.call_hashes.as_deref() {
+            Some(pallet) => emit_call_hashes(pallet, call, &vis),
+            None => quote! {},
+        };
+        let calls_table = if options.calls_table {
+            emit_calls_table(call, &vis)
+        } else {
+            quote! {}
+        };
+        let for_each_call = if options.for_each_call {
+            emit_for_each_call(call, &vis)
+        } else {
+            quote! {}
+        };
+        let call_filter = match options.call_filter.as_ref() {
+            Some(filter) => emit_call_filter(call, filter, &enum_generics, &vis),
+            None => quote! {},
+        };
+        if options.constructor_examples && !options.trait_constructors {
+            return Err(Error::Config(
+                "constructor examples require `trait_constructors`".to_string(),
+            ));
+        }
+        let types_trait = match options.types_trait || options.trait_constructors {
+            true if keep_generics => {
+                return Err(Error::Config(
+                    "a types trait requires the generics to be erased".to_string(),
+                ))
+            }
+            true if options.trait_constructors => {
+                let types_trait = emit_types_trait(call, &vis)?;
+                let constructors = emit_trait_constructors(call, &variants, options, &vis)?;
+                quote! {
+                    #types_trait
+                    #constructors
+                }
+            }
+            true => emit_types_trait(call, &vis)?,
+            false => quote! {},
+        };
+        let ignore = match skipped.as_ref() {
+            Some((ident, _)) => quote! { #name::#ident(_, never) => match *never {}, },
+            None => quote! {},
+        };
+        let size_hint = if options.size_hint {
+            emit_size_hint(call, &enum_generics, &codec_crate, &vis)
+        } else {
+            quote! {}
+        };
+        let static_weights = match options.static_weights {
+            true if options.weights_source.is_none() => {
+                return Err(Error::Config(
+                    "`static_weights` requires the `weights_source` of the pallet".to_string(),
+                ))
+            }
+            true => emit_static_weights(call, &enum_generics, &ignore, &vis),
+            false => quote! {},
+        };
+        let required_origin = match options.required_origin {
+            true if options.origin_source.is_none() => {
+                return Err(Error::Config(
+                    "`required_origin` requires the `origin_source` of the pallet".to_string(),
+                ))
+            }
+            true => emit_required_origin(call, &enum_generics, &ignore, &vis),
+            false => quote! {},
+        };
+        let value = match options.scale_value {
+            true if keep_generics => {
+                return Err(Error::Config(
+                    "`scale_value` conversions require the generics to be erased".to_string(),
+                ))
+            }
+            true if skipped.is_some() => return skipped_error("`scale_value` conversions"),
+            true if !lifetimes.is_empty() => return borrowed_error("`scale_value` conversions"),
+            true => value::emit(call, &variants, &vis)?,
+            false => quote! {},
+        };
+        let lookup = match options.lookup_helpers {
+            true if keep_generics => {
+                return Err(Error::Config(
+                    "lookup helpers require the generics to be erased".to_string(),
+                ))
+            }
+            true => lookup::emit(call, &variants, &impl_generics, &generics, &vis)?,
+            false => quote! {},
+        };
+        let call_name = if explicit_indices {
+            emit_call_name(call, &repr, &enum_generics, &ignore, &vis)
+        } else {
+            quote! {}
+        };
+        let mut variants = variants
+            .into_iter()
+            .map(|variant| quote! { #variant })
+            .collect::<Vec<_>>();
+        if let Some((ident, params)) = skipped.as_ref() {
+            let codec_skip = if self.config.options.without_codec {
+                quote! {}
+            } else {
+                quote! { #[codec(skip)] }
+            };
+            // the generic parameters might not be used by any of the calls
+            variants.push(quote! {
+                #[doc(hidden)]
+                #codec_skip
+                #ident(core::marker::PhantomData<( #params )>, core::convert::Infallible)
+            });
+        }
+        let attrs = &call.attrs;
+        let decl_generics = if keep_generics {
+            quote! { #input_generics }
+        } else {
+            generics.clone()
+        };
+        let aliases = self.emit_aliases(call, &vis)?;
+
+        Ok(quote! {
+            #( #attrs )*
+            #repr
+            #vis enum #name #decl_generics #where_clause {
+                #( #variants ),*
+            }
+
+            #aliases
+            #concrete
+            #value
+            #lookup
+            #types_trait
+            #required_origin
+            #static_weights
+            #size_hint
+            #call_hashes
+            #calls_table
+            #for_each_call
+            #call_filter
+            #examples
+            #decode_tests
+            #call_name
+        })
+    }
+
+    /// Emits the `types` module with the configured aliases of the generics
+    /// and a `Call` alias that applies them, if all generics have an alias
+    fn emit_aliases(&self, call: &ExpandedCall, vis: &Visibility) -> Result<TokenStream> {
+        if self.config.options.keep_generics {
+            return self.emit_config_aliases(call, vis);
+        }
+        let aliases = options::parse_types(&self.config.options.aliases)?;
+        if aliases.is_empty() {
+            return Ok(quote! {});
+        }
+        // items of the module need to be visible in the parent module
+        let inner_vis = match vis {
+            Visibility::Inherited => quote! { pub(super) },
+            vis => quote! { #vis },
+        };
+
+        let mut types = Vec::with_capacity(aliases.len());
+        for (generic, ty) in &aliases {
+            let generic = options::parse_config("alias", generic, parse_ident)?;
+            types.push(quote! { #inner_vis type #generic = #ty; });
+        }
+        let name = &call.name;
+        let call_alias = if call
+            .generics
+            .values()
+            .all(|generic| aliases.contains_key(generic.as_str()))
+        {
+            let generics = call
+                .generics
+                .values()
+                .map(|generic| parse_ident(generic))
+                .collect::<syn::Result<Vec<_>>>()?;
+            let lifetimes = &call.lifetimes;
+            let params = if lifetimes.is_empty() {
+                quote! {}
+            } else {
+                quote! { <#( #lifetimes ),*> }
+            };
+            quote! { #inner_vis type #name #params = super::#name<#( #lifetimes, )* #( #generics ),*>; }
+        } else {
+            quote! {}
+        };
+
+        Ok(quote! {
+            #vis mod types {
+                #( #types )*
+                #call_alias
+            }
+        })
+    }
+
+    /// Emits a `<Generic>Of<T>` alias for every type that would be erased if
+    /// the `T: Config` generic was not kept
+    fn emit_config_aliases(&self, call: &ExpandedCall, vis: &Visibility) -> Result<TokenStream> {
+        let generics = &call.input_generics;
+        let mut aliases = Vec::with_capacity(call.generics.len());
+        for (ty, generic) in &call.generics {
+            let alias = options::parse_config(
+                "generic name",
+                &format!("{}Of", generic),
+                syn::parse_str::<Ident>,
+            )?;
+            let ty = &call.types[ty];
+            aliases.push(quote! {
+                #[allow(type_alias_bounds)]
+                #vis type #alias #generics = #ty;
+            });
+        }
+        Ok(quote! { #( #aliases )* })
+    }
+
+    /// The visibility of the generated items, by default `pub`
+    pub(crate) fn visibility(&self) -> Result<Visibility> {
+        let vis = self.config.options.visibility.as_deref().unwrap_or("pub");
+        options::parse_config("visibility", vis, syn::parse_str::<Visibility>)
+    }
+
+    /// Prepends the configured imports and wraps everything in a module if
+    /// `module` is set
+    pub(crate) fn wrap(&self, module: Option<&str>, tokens: TokenStream) -> Result<TokenStream> {
+        let imports = self.config.options.parse_imports()?;
+        let alloc = if self.config.options.no_std {
+            ensure_no_std(&quote! { #( #imports )* })?;
+            quote! {
+                #[allow(unused_imports)]
+                use alloc::vec::Vec;
+            }
+        } else {
+            quote! {}
+        };
+        let tokens = quote! {
+            #alloc
+            #( #imports )*
+            #tokens
+        };
+
+        if let Some(module) = module {
+            let module = options::parse_config("module", module, syn::parse_str::<Ident>)?;
+            Ok(quote! {
+                pub mod #module {
+                    #tokens
+                }
+            })
+        } else {
+            Ok(tokens)
+        }
+    }
+
+    /// Returns a `blake2_256` hash of the normalized output of `expand`
+    ///
+    /// Since the expansion is deterministic, this can be used to verify that a
+    /// generated file is up to date in reproducible builds.
+    pub fn fingerprint(&self) -> Result<[u8; 32]> {
+        Ok(blake2_256(self.expand()?.to_string().as_bytes()))
+    }
+
+    /// Returns a `blake2_256` hash of the call names, indices and field
+    /// types, which ignores docs, derives and attributes
+    pub fn signature_hash(&self) -> Result<[u8; 32]> {
+        let call = self.expand_call()?;
+        let mut surface = String::new();
+        for v in &call.variants {
+            let fields = v
+                .variant
+                .fields
+                .iter()
+                .map(|field| {
+                    let ty = type_to_string(&field.ty);
+                    if field.attrs.iter().any(attributes::is_compact) {
+                        format!("compact {}", ty)
+                    } else {
+                        ty
+                    }
+                })
+                .collect::<Vec<_>>();
+            surface.push_str(&format!(
+                "{}:{}({});",
+                v.index,
+                v.call_name,
+                fields.join(",")
+            ));
+        }
+        Ok(blake2_256(surface.as_bytes()))
+    }
+
+    /// Expands the pallet call and formats the output with `prettyplease`
+    #[cfg(feature = "pretty")]
+    pub fn expand_pretty(&self) -> Result<String> {
+        Ok(output::pretty(self.expand()?)?)
+    }
+
+    /// Expands the pallet call and writes the generated code to the given path
+    ///
+    /// Returns `true` if the file was written.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use substrate_pallet_call_extract::{PalletCallConfig, WriteMode};
+    /// PalletCallConfig::default()
+    ///     .parse_pallet("pallet-balances")
+    ///     .unwrap()
+    ///     .write_to_file("src/balances.rs", WriteMode::IfChanged)
+    ///     .unwrap();
+    /// ```
+    pub fn write_to_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        mode: WriteMode,
+    ) -> anyhow::Result<bool> {
+        let content = self.to_source()?;
+        Ok(output::write(path.as_ref(), &content, mode)?)
+    }
+
+    /// Expands the pallet call and renders it as source code, pretty
+    /// formatted if the `pretty` feature is enabled
+    pub fn to_source(&self) -> Result<String> {
+        let source = output::render(self.expand()?)?;
+        let source = match self.header() {
+            Some(header) => format!("{}\n{}", header, source),
+            None => source,
+        };
+        output::finish(source, Some(self.config.options()))
+    }
+
+    /// Renders the configured `header` as line comments
+    pub fn header(&self) -> Option<String> {
+        let template = self.config.options.header.as_ref()?;
+        let input = &self.input;
+        let input = quote!(#input).to_string();
+        let input_hash = blake2_256(input.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        let pallet = self
+            .config
+            .options
+            .module
+            .clone()
+            .unwrap_or_else(|| self.input.ident.to_string());
+        let options = CallOptions {
+            header: None,
+            preamble: None,
+            rustfmt: None,
+            ..self.config.options.clone()
+        };
+        let options = serde_json::to_string(&options).expect("options are serializable");
+        let header = template
+            .replace("{tool}", env!("CARGO_PKG_NAME"))
+            .replace("{version}", env!("CARGO_PKG_VERSION"))
+            .replace("{pallet}", &pallet)
+            .replace("{input_hash}", &input_hash)
+            .replace("{options}", &options);
+        let lines = header
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    "//".to_string()
+                } else {
+                    format!("// {}", line)
+                }
+            })
+            .collect::<Vec<_>>();
+        Some(lines.join("\n"))
+    }
+}
+
+/// Finds the `Call` enum in the `pallet` module, or the first `Call` enum
+fn find_call(items: &[syn::Item]) -> Option<&syn::ItemEnum> {
+    let mut modules = Vec::new();
+    for item in items {
+        match item {
+            syn::Item::Enum(item) if item.ident == "Call" => return Some(item),
+            syn::Item::Mod(module) => {
+                if let Some((_, items)) = module.content.as_ref() {
+                    if module.ident == "pallet" {
+                        modules.insert(0, items);
+                    } else {
+                        modules.push(items);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    modules.into_iter().find_map(|items| find_call(items))
+}
+
+/// The generics of the emitted enum, for the impls of the companion items
+struct EnumGenerics {
+    /// The generics of the enum type
+    ty: TokenStream,
+    /// The generics of the impl
+    params: TokenStream,
+    /// The where clause of the impl
+    where_clause: TokenStream,
+}
+
+/// Emits the `<Name>Name` enum with the index of every call, and the
+/// `call_name` and `call_index` functions
+fn emit_call_name(
+    call: &ExpandedCall,
+    repr: &TokenStream,
+    generics: &EnumGenerics,
+    ignore: &TokenStream,
+    vis: &Visibility,
+) -> TokenStream {
+    let EnumGenerics {
+        ty,
+        params,
+        where_clause,
+    } = generics;
+    let name = &call.name;
+    let call_name = format_ident!("{}Name", name);
+    let names = call
+        .variants
+        .iter()
+        .map(|v| &v.variant.ident)
+        .collect::<Vec<_>>();
+    let indices = call
+        .variants
+        .iter()
+        .map(|v| Literal::u8_unsuffixed(v.index))
+        .collect::<Vec<_>>();
+    let doc = format!(
+        " The names of all calls of [`{}`] with their call index",
+        name
+    );
+    quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #repr
+        #vis enum #call_name {
+            #( #names = #indices ),*
+        }
+
+        impl core::convert::TryFrom<u8> for #call_name {
+            type Error = u8;
+
+            fn try_from(index: u8) -> Result<Self, Self::Error> {
+                match index {
+                    #( #indices => Ok(#call_name::#names), )*
+                    _ => Err(index),
+                }
+            }
+        }
+
+        impl #params #name #ty #where_clause {
+            /// The name of this call
+            #vis fn call_name(&self) -> #call_name {
+                match self {
+                    #( #name::#names { .. } => #call_name::#names, )*
+                    #ignore
+                }
+            }
+
+            /// The index of this call, as it is encoded
+            #vis fn call_index(&self) -> u8 {
+                self.call_name() as u8
+            }
+        }
+    }
+}
+
+/// Emits the `<Name>Filter` that matches the allowed calls
+fn emit_call_filter(
+    call: &ExpandedCall,
+    filter: &CallFilterList,
+    generics: &EnumGenerics,
+    vis: &Visibility,
+) -> TokenStream {
+    let EnumGenerics {
+        ty,
+        params,
+        where_clause,
+    } = generics;
+    let name = &call.name;
+    let allowed = call
+        .variants
+        .iter()
+        .filter(|v| filter.matches(&v.call_name))
+        .map(|v| &v.variant.ident);
+    let filter_name = format_ident!("{}Filter", name);
+    let doc = format!(" Matches the allowed calls of [`{}`]", name);
+    let contains = match filter.contains_feature.as_deref() {
+        Some(feature) => quote! {
+            #[cfg(feature = #feature)]
+            impl #params frame_support::traits::Contains<#name #ty> for #filter_name #where_clause {
+                fn contains(call: &#name #ty) -> bool {
+                    #filter_name.matches(call)
+                }
+            }
+        },
+        None => quote! {},
+    };
+    quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        #vis struct #filter_name;
+
+        impl #filter_name {
+            /// Whether the call is allowed
+            #vis fn matches #params (&self, call: &#name #ty) -> bool #where_clause {
+                #[allow(unreachable_patterns)]
+                match call {
+                    #( #name::#allowed { .. } => true, )*
+                    _ => false,
+                }
+            }
+        }
+
+        #contains
+    }
+}
+
+/// Emits the `encoded_size_hint` function
+fn emit_size_hint(
+    call: &ExpandedCall,
+    generics: &EnumGenerics,
+    codec_crate: &Path,
+    vis: &Visibility,
+) -> TokenStream {
+    let EnumGenerics {
+        ty,
+        params,
+        where_clause,
+    } = generics;
+    let name = &call.name;
+    quote! {
+        impl #params #name #ty #where_clause {
+            /// The estimated size of the encoded call, in bytes
+            #vis fn encoded_size_hint(&self) -> usize
+            where
+                Self: #codec_crate::Encode,
+            {
+                #codec_crate::Encode::size_hint(self)
+            }
+        }
+    }
+}
+
+/// Emits the `<Name>Weight` type and the `weight` function
+fn emit_static_weights(
+    call: &ExpandedCall,
+    generics: &EnumGenerics,
+    ignore: &TokenStream,
+    vis: &Visibility,
+) -> TokenStream {
+    let EnumGenerics {
+        ty,
+        params,
+        where_clause,
+    } = generics;
+    let name = &call.name;
+    let weight_name = format_ident!("{}Weight", name);
+    let arms = call.variants.iter().map(|v| {
+        let ident = &v.variant.ident;
+        let weight = match v.weight {
+            Some(CallWeight {
+                ref_time,
+                proof_size,
+                reads,
+                writes,
+            }) => quote! {
+                Some(#weight_name {
+                    ref_time: #ref_time,
+                    proof_size: #proof_size,
+                    reads: #reads,
+                    writes: #writes,
+                })
+            },
+            None => quote! { None },
+        };
+        quote! { #name::#ident { .. } => #weight, }
+    });
+    quote! {
+        /// The benchmarked weight of a call, without the components that
+        /// depend on its parameters
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis struct #weight_name {
+            /// The base execution time, in picoseconds
+            pub ref_time: u64,
+            /// The base proof size, in bytes
+            pub proof_size: u64,
+            /// The number of database reads
+            pub reads: u64,
+            /// The number of database writes
+            pub writes: u64,
+        }
+
+        impl #params #name #ty #where_clause {
+            /// The benchmarked weight of this call, if it's known
+            #vis fn weight(&self) -> Option<#weight_name> {
+                match self {
+                    #( #arms )*
+                    #ignore
+                }
+            }
+        }
+    }
+}
+
+/// Emits the `<Name>Origin` type and the `required_origin` function
+fn emit_required_origin(
+    call: &ExpandedCall,
+    generics: &EnumGenerics,
+    ignore: &TokenStream,
+    vis: &Visibility,
+) -> TokenStream {
+    let EnumGenerics {
+        ty,
+        params,
+        where_clause,
+    } = generics;
+    let name = &call.name;
+    let origin_name = format_ident!("{}Origin", name);
+    let arms = call.variants.iter().map(|v| {
+        let ident = &v.variant.ident;
+        let origin = match v.origin.as_ref() {
+            Some(OriginKind::Root) => quote! { #origin_name::Root },
+            Some(OriginKind::Signed) => quote! { #origin_name::Signed },
+            Some(OriginKind::None) => quote! { #origin_name::None },
+            Some(OriginKind::Custom(origin)) => quote! { #origin_name::Custom(#origin) },
+            None => quote! { #origin_name::Unknown },
+        };
+        quote! { #name::#ident { .. } => #origin, }
+    });
+    quote! {
+        /// The origin a call requires
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum #origin_name {
+            /// `ensure_root`
+            Root,
+            /// `ensure_signed`
+            Signed,
+            /// `ensure_none`, for unsigned transactions and inherents
+            None,
+            /// `ensure_origin` of a configured origin, like `T::AdminOrigin`
+            Custom(&'static str),
+            /// The call has no known origin check
+            Unknown,
+        }
+
+        impl #params #name #ty #where_clause {
+            /// The origin this call requires
+            #vis fn required_origin(&self) -> #origin_name {
+                match self {
+                    #( #arms )*
+                    #ignore
+                }
+            }
+        }
+    }
+}
+
+/// Emits the `<NAME>_HASHES` constant with the hash of `<pallet>::<call>` for
+/// every call
+fn emit_call_hashes(pallet: &str, call: &ExpandedCall, vis: &Visibility) -> TokenStream {
+    let hashes_name = format_ident!("{}_HASHES", call.name.to_string().to_screaming_snake_case());
+    let hashes = call.variants.iter().map(|v| {
+        let call_name = &v.call_name;
+        let hash = blake2_256(format!("{}::{}", pallet, call_name).as_bytes());
+        let bytes = hash.iter().map(|b| Literal::u8_unsuffixed(*b));
+        quote! { (#call_name, [#( #bytes ),*]) }
+    });
+    let doc = format!(
+        " The `blake2_256` hashes of `{}::<call>` for every call, by call name",
+        pallet
+    );
+    quote! {
+        #[doc = #doc]
+        #vis const #hashes_name: &[(&str, [u8; 32])] = &[#( #hashes ),*];
+    }
+}
+
+/// Emits the `<Name>Meta` type and the `<NAME>_CALLS` table that describes
+/// every call
+fn emit_calls_table(call: &ExpandedCall, vis: &Visibility) -> TokenStream {
+    let meta = format_ident!("{}Meta", call.name);
+    let table = format_ident!("{}_CALLS", call.name.to_string().to_screaming_snake_case());
+    let calls = call.variants.iter().map(|v| {
+        let info = CallInfo::new(v);
+        let name = &info.name;
+        let index = Literal::u8_unsuffixed(info.index);
+        let arg_names = info
+            .args
+            .iter()
+            .map(|arg| arg.name.clone().unwrap_or_default());
+        let arg_types = info.args.iter().map(|arg| &arg.ty);
+        let docs = info.summary();
+        quote! {
+            #meta {
+                name: #name,
+                index: #index,
+                arg_names: &[#( #arg_names ),*],
+                arg_types: &[#( #arg_types ),*],
+                docs: #docs,
+            }
+        }
+    });
+    let doc = format!(" Describes a call of [`{}`]", call.name);
+    let calls_doc = format!(" All calls of [`{}`]", call.name);
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #vis struct #meta {
+            /// The name of the call, like `transfer`
+            pub name: &'static str,
+            /// The index of the call, as it is encoded
+            pub index: u8,
+            /// The names of the parameters, empty for unnamed parameters
+            pub arg_names: &'static [&'static str],
+            /// The original types of the parameters, like `T::Balance`
+            pub arg_types: &'static [&'static str],
+            /// The first paragraph of the call's docs
+            pub docs: &'static str,
+        }
+
+        #[doc = #calls_doc]
+        #vis static #table: &[#meta] = &[#( #calls ),*];
+    }
+}
+
+/// Emits the `<Name>Types` trait with an associated type for every generic
+/// and the `<Name>For<C>` alias
+fn emit_types_trait(call: &ExpandedCall, vis: &Visibility) -> Result<TokenStream> {
+    if call.generics.is_empty() {
+        return Ok(quote! {});
+    }
+    let name = &call.name;
+    let trait_name = format_ident!("{}Types", name);
+    let alias = format_ident!("{}For", name);
+    let generics = call
+        .generics
+        .values()
+        .map(|generic| parse_ident(generic))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let lifetimes = &call.lifetimes;
+    let doc = format!(" The types of the generics of [`{}`]", name);
+    let alias_doc = format!(" [`{}`] with the types of `C`", name);
+    Ok(quote! {
+        #[doc = #doc]
+        #vis trait #trait_name {
+            #( type #generics; )*
+        }
+
+        #[doc = #alias_doc]
+        #vis type #alias<#( #lifetimes, )* C> = #name<#( #lifetimes, )* #( <C as #trait_name>::#generics ),*>;
+    })
+}
+
+/// Emits a constructor function for every call that is generic over the
+/// `<Name>Types` trait
+///
+/// The `variants` are the variants of the generic enum, as they are emitted.
+fn emit_trait_constructors(
+    call: &ExpandedCall,
+    variants: &[Variant],
+    options: &CallOptions,
+    vis: &Visibility,
+) -> Result<TokenStream> {
+    let name = &call.name;
+    let trait_name = format_ident!("{}Types", name);
+    let alias = format_ident!("{}For", name);
+    let lifetimes = &call.lifetimes;
+    let codec_crate = if options.without_codec {
+        None
+    } else {
+        let codec_crate = options.parse_codec_crate()?;
+        Some(quote!(#codec_crate).to_string().replace(' ', ""))
+    };
+    let mut types = BTreeMap::new();
+    for generic in call.generics.values() {
+        let ident = parse_ident(generic)?;
+        types.insert(
+            generic.as_str(),
+            syn::parse_quote!(<C as #trait_name>::#ident),
+        );
+    }
+    let (params, output) = if types.is_empty() && lifetimes.is_empty() {
+        (quote! {}, quote! { #name })
+    } else if types.is_empty() {
+        (
+            quote! { <#( #lifetimes ),*> },
+            quote! { #name<#( #lifetimes ),*> },
+        )
+    } else {
+        (
+            quote! { <#( #lifetimes, )* C: #trait_name> },
+            quote! { #alias<#( #lifetimes, )* C> },
+        )
+    };
+
+    let mut constructors = Vec::with_capacity(variants.len());
+    for (v, variant) in call.variants.iter().zip(variants) {
+        let fn_name = parse_ident(&v.call_name)?;
+        let args = value::bindings(&variant.fields)
+            .into_iter()
+            .zip(variant.fields.iter())
+            .map(|(binding, field)| {
+                let ty = &field.ty;
+                let ty = concrete::substitute(quote! { #ty }, &types);
+                quote! { #binding: #ty }
+            });
+        let ident = &variant.ident;
+        let pattern = concrete::pattern(&variant.fields);
+        let doc = format!(" Creates a `{}` call", v.call_name);
+        let example = if options.constructor_examples {
+            let example = constructor_example(
+                &v.call_name,
+                variant.fields.len(),
+                !types.is_empty(),
+                codec_crate.as_deref(),
+            );
+            attributes::doc_attributes(&example)
+        } else {
+            Vec::new()
+        };
+        constructors.push(quote! {
+            #[doc = #doc]
+            #( #example )*
+            #vis fn #fn_name #params ( #( #args ),* ) -> #output {
+                #name::#ident #pattern
+            }
+        });
+    }
+    Ok(quote! { #( #constructors )* })
+}
+
+/// The doc lines of an example that calls the constructor with placeholder
+/// values, `Chain` is a placeholder for the implementation of the
+/// `<Name>Types` trait, the call is encoded with the `codec` crate if any
+fn constructor_example(
+    call_name: &str,
+    args: usize,
+    generic: bool,
+    codec: Option<&str>,
+) -> Vec<String> {
+    let turbofish = if generic { "::<Chain>" } else { "" };
+    let args = vec!["Default::default()"; args].join(", ");
+    let mut lines = vec![
+        String::new(),
+        " # Example".to_string(),
+        String::new(),
+        " ```ignore".to_string(),
+        format!(" let call = {}{}({});", call_name, turbofish, args),
+    ];
+    if let Some(codec) = codec {
+        lines.push(format!(" let encoded = {}::Encode::encode(&call);", codec));
+    }
+    lines.push(" ```".to_string());
+    lines
+}
+
+/// Emits the `for_each_<name>!` macro, which expands a block for every variant
+/// with the variant's name and fields
+fn emit_for_each_call(call: &ExpandedCall, vis: &Visibility) -> TokenStream {
+    let snake_name = call.name.to_string().to_snake_case();
+    let macro_name = format_ident!("for_each_{}", snake_name);
+    let helper = format_ident!("__for_each_{}", snake_name);
+    let calls = call.variants.iter().map(|v| {
+        let name = &v.variant.ident;
+        let fields = match &v.variant.fields {
+            Fields::Named(fields) => {
+                let fields = fields.named.iter().map(|field| {
+                    let ident = &field.ident;
+                    let ty = &field.ty;
+                    quote! { #ident: #ty }
+                });
+                quote! { { #( #fields ),* } }
+            }
+            Fields::Unnamed(fields) => {
+                let types = fields.unnamed.iter().map(|field| &field.ty);
+                quote! { ( #( #types ),* ) }
+            }
+            Fields::Unit => quote! { () },
+        };
+        quote! { #helper!(#name, #fields); }
+    });
+    let doc = format!(
+        " Expands the block for every variant of [`{}`], with the variant's name \
+         and its fields like `(Source, Balance)`",
+        call.name
+    );
+    let example = format!(" {}! {{ ($name, $fields) => {{ /* .. */ }} }}", macro_name);
+    // `pub` macros can only be exported at the crate root
+    let (export, import) = match vis {
+        Visibility::Public(_) => (quote! { #[macro_export] }, quote! {}),
+        Visibility::Inherited => (quote! {}, quote! {}),
+        vis => (
+            quote! {},
+            quote! {
+                #[allow(unused_imports)]
+                #vis use #macro_name;
+            },
+        ),
+    };
+    quote! {
+        #[doc = #doc]
+        ///
+        /// ```ignore
+        #[doc = #example]
+        /// ```
+        #[allow(unused_macros)]
+        #export
+        macro_rules! #macro_name {
+            (($d:tt $name:ident, $d2:tt $fields:ident) => { $($body:tt)* }) => {
+                macro_rules! #helper {
+                    ($d $name:ident, $d2 $fields:tt) => { $($body)* };
+                }
+                #( #calls )*
+            };
+        }
+        #import
+    }
+}
+
+/// Renders the predicates as they are stored in the `CallOptions`
+fn predicates_to_strings(predicates: impl IntoIterator<Item = WherePredicate>) -> Vec<String> {
+    predicates
+        .into_iter()
+        .map(|predicate| quote!(#predicate).to_string())
+        .collect()
+}
+
+/// Hashes the given data with `blake2b` and a 32 byte output
+pub(crate) fn blake2_256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake2b256::new();
+    hasher.update(data);
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&hasher.finalize());
+    hash
+}
+
+/// Information about a call of the `pallet::Call` that is passed to the
+/// callbacks of the `PalletCallConfig`
+#[derive(Clone, Copy)]
+pub struct VariantInfo<'a> {
+    /// The original name of the call, like `transfer`
+    pub call_name: &'a str,
+    /// The name of the generated variant, like `Transfer`
+    pub variant_name: &'a Ident,
+    /// The index of the call, as it is encoded
+    pub index: u8,
+    /// The original variant
+    pub variant: &'a Variant,
+}
+
+/// Reconstructs the signature of the dispatchable from the original variant,
+/// like `fn transfer(origin, dest: <T::Lookup as StaticLookup>::Source)`
+fn call_signature(variant: &Variant) -> String {
+    let mut params = vec!["origin".to_string()];
+    for field in variant.fields.iter() {
+        let ty = type_to_string(&field.ty);
+        if let Some(ident) = field.ident.as_ref() {
+            params.push(format!("{}: {}", ident, ty));
+        } else {
+            params.push(ty);
+        }
+    }
+    format!("fn {}({})", variant.ident, params.join(", "))
+}
+
+/// Fails if the tokens contain a path that starts with `std`
+fn ensure_no_std(tokens: &TokenStream) -> Result<()> {
+    let mut prev_colon = false;
+    let mut iter = tokens.clone().into_iter().peekable();
+    while let Some(token) = iter.next() {
+        match &token {
+            TokenTree::Group(group) => ensure_no_std(&group.stream())?,
+            TokenTree::Ident(ident) if ident == "std" && !prev_colon => {
+                if let Some(TokenTree::Punct(punct)) = iter.peek() {
+                    if punct.as_char() == ':' {
+                        return Err(Error::Config(
+                            "`std` paths are not allowed in `no_std` mode".to_string(),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+        prev_colon = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == ':');
+    }
+    Ok(())
+}
+
+/// Parses an identifier, keywords like `type` become raw identifiers like
+/// `r#type`
+pub(crate) fn parse_ident(name: &str) -> syn::Result<Ident> {
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    syn::parse_str::<Ident>(name)
+        .or_else(|err| syn::parse_str::<Ident>(&format!("r#{}", name)).map_err(|_| err))
+}
+
+/// Renders the type as it would be written in code, without the additional
+/// whitespace of the `TokenStream` representation
+pub(crate) fn type_to_string(ty: &Type) -> String {
+    let mut s = quote!(#ty).to_string();
+    for (from, to) in &[
+        (" :: ", "::"),
+        (":: ", "::"),
+        ("< ", "<"),
+        (" <", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        ("& ", "&"),
+        ("[ ", "["),
+        (" ]", "]"),
+        (" ;", ";"),
+        ("( ", "("),
+        (" )", ")"),
+    ] {
+        s = s.replace(from, to);
+    }
+    s
+}
+
+/// A temporary directory of the test that is unique to the test process, so
+/// concurrent test runs don't share it
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!(
+        "substrate_pallet_call_extract_{}_{}",
+        name,
+        std::process::id()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_extract_balances() {
+        let expanded = PalletCallConfig::default()
+            .push_derive(syn::parse_str("std::fmt::Debug").unwrap())
+            .parse_pallet("pallet-balances")
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode, std::fmt::Debug)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance),
+                SetBalance(Source, #[codec(compact)] Balance, #[codec(compact)] Balance),
+                ForceTransfer(Source, Source, #[codec(compact)] Balance),
+                TransferKeepAlive(Source, #[codec(compact)] Balance)
+            }
+        };
+
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    const CALL: &str = r#"
+        pub enum Call<T: Config> {
+            #[doc(hidden)]
+            #[codec(skip)]
+            __Ignore(PhantomData<(T,)>, Never),
+            /// Transfer some balance
+            transfer(<T::Lookup as StaticLookup>::Source, #[codec(compact)] T::Balance),
+            set_balance(<T::Lookup as StaticLookup>::Source, #[codec(compact)] T::Balance),
+            remark(Vec<u8>),
+        }
+    "#;
+
+    #[test]
+    fn fingerprint_is_stable() {
+        let first = PalletCallConfig::default().parse(CALL).unwrap();
+        let second = PalletCallConfig::default().parse(CALL).unwrap();
+        assert_eq!(first.fingerprint().unwrap(), second.fingerprint().unwrap());
+
+        let renamed = PalletCallConfig::default()
+            .name("BalancesCall")
+            .parse(CALL)
+            .unwrap();
+        assert_ne!(first.fingerprint().unwrap(), renamed.fingerprint().unwrap());
+    }
+
+    #[test]
+    fn can_wrap_in_module() {
+        let mut config = PalletCallConfig::default().module("balances");
+        config
+            .push_import_str("use codec::{Encode, Decode};")
+            .unwrap();
+        let expanded = config.parse(CALL).unwrap().expand().unwrap();
+
+        let expected = quote! {
+            pub mod balances {
+                use codec::{Encode, Decode};
+                #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+                pub enum Call<Source, Balance> {
+                    Transfer(Source, #[codec(compact)] Balance),
+                    SetBalance(Source, #[codec(compact)] Balance),
+                    Remark(Vec<u8>)
+                }
+            }
+        };
+
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_reuse_config() {
+        let base = PalletCallConfig::default().variant_name(|name| name.to_uppercase());
+
+        let call = base.clone().parse(CALL).unwrap().expand().unwrap();
+        let renamed = base
+            .name("BalancesCall")
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                TRANSFER(Source, #[codec(compact)] Balance),
+                SET_BALANCE(Source, #[codec(compact)] Balance),
+                REMARK(Vec<u8>)
+            }
+        };
+        assert_eq!(call.to_string(), expected.to_string());
+        assert!(renamed.to_string().contains("pub enum BalancesCall"));
+    }
+
+    #[test]
+    fn can_filter_calls() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer", "remark"])
+            .exclude_calls(["remark"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_override_calls() {
+        let expanded = PalletCallConfig::default()
+            .override_call(
+                "set_balance",
+                CallOverride {
+                    name: Some("ForceSetBalance".to_string()),
+                    attributes: vec!["#[cfg(feature = \"governance\")]".to_string()],
+                    parameter_style: Some(ParameterStyle::Named),
+                    substitutions: vec![("Balance".to_string(), "u128".to_string())]
+                        .into_iter()
+                        .collect(),
+                },
+            )
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance),
+                #[cfg(feature = "governance")]
+                ForceSetBalance { arg0: Source, #[codec(compact)] arg1: u128 },
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_transform_field_attributes() {
+        let expanded = PalletCallConfig::default()
+            .field_attributes(|field, info| {
+                if info.call_name == "remark" {
+                    vec![syn::parse_quote!(#[serde(with = "hex")])]
+                } else {
+                    field.attrs.clone()
+                }
+            })
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance),
+                SetBalance(Source, #[codec(compact)] Balance),
+                Remark(#[serde(with = "hex")] Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn keeps_indices_of_filtered_calls() {
+        let expanded = PalletCallConfig::default()
+            .exclude_calls(["set_balance"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                #[codec(index = 0)]
+                Transfer(Source, #[codec(compact)] Balance),
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+
+        // the expected enum encodes the call after the skipped one with its
+        // original index
+        #[derive(codec::Encode)]
+        enum Call<Source, Balance> {
+            #[codec(index = 0)]
+            Transfer(Source, #[codec(compact)] Balance),
+            #[codec(index = 2)]
+            Remark(Vec<u8>),
+        }
+        let transfer = codec::Encode::encode(&Call::<u64, u128>::Transfer(1, 2));
+        let remark = codec::Encode::encode(&Call::<u64, u128>::Remark(vec![1]));
+        assert_eq!(transfer[0], 0);
+        assert_eq!(remark, vec![2, 4, 1]);
+    }
+
+    #[test]
+    fn can_transform_docs() {
+        let expanded = PalletCallConfig::default()
+            .docs(|lines, info| {
+                let mut lines = lines.to_vec();
+                lines.push(format!(" Call index: {}", info.index));
+                lines
+            })
+            .include_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                #[doc = " Transfer some balance"]
+                #[doc = " Call index: 0"]
+                Transfer(Source, #[codec(compact)] Balance)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_add_signature_docs() {
+        let expanded = PalletCallConfig::default()
+            .signature_docs()
+            .include_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                #[doc = " origin call: `fn transfer(origin, <T::Lookup as StaticLookup>::Source, T::Balance)`"]
+                Transfer(Source, #[codec(compact)] Balance)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_propagate_deprecated() {
+        let expanded = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                /// DEPRECATED: use `transfer_keep_alive` instead
+                transfer(T::AccountId),
+                #[deprecated]
+                transfer_all(T::AccountId),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<AccountId> {
+                #[deprecated(note = "use `transfer_keep_alive` instead")]
+                Transfer(AccountId),
+                #[deprecated]
+                TransferAll(AccountId)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_wrap_compact_fields() {
+        let expanded = PalletCallConfig::default()
+            .compact_mode(CompactMode::Wrap)
+            .include_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, codec::Compact<Balance>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_use_codec_crate_path() {
+        let expanded = PalletCallConfig::default()
+            .codec_crate("::frame_support::codec")
+            .compact_mode(CompactMode::Wrap)
+            .include_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(
+                Clone, PartialEq, Eq,
+                ::frame_support::codec::Encode,
+                ::frame_support::codec::Decode,
+            )]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, ::frame_support::codec::Compact<Balance>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_set_visibility() {
+        let expanded = PalletCallConfig::default()
+            .visibility("pub(crate)")
+            .include_calls(["remark"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub(crate) enum Call {
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_explicit_indices() {
+        let expanded = PalletCallConfig::default()
+            .explicit_indices()
+            .exclude_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            #[repr(u8)]
+            pub enum Call<Source, Balance> {
+                SetBalance(Source, #[codec(compact)] Balance) = 1,
+                Remark(Vec<u8>) = 2
+            }
+
+            #[doc = " The names of all calls of [`Call`] with their call index"]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            #[repr(u8)]
+            pub enum CallName {
+                SetBalance = 1,
+                Remark = 2
+            }
+
+            impl core::convert::TryFrom<u8> for CallName {
+                type Error = u8;
+
+                fn try_from(index: u8) -> Result<Self, Self::Error> {
+                    match index {
+                        1 => Ok(CallName::SetBalance),
+                        2 => Ok(CallName::Remark),
+                        _ => Err(index),
+                    }
+                }
+            }
+
+            impl<Source, Balance> Call<Source, Balance> {
+                /// The name of this call
+                pub fn call_name(&self) -> CallName {
+                    match self {
+                        Call::SetBalance { .. } => CallName::SetBalance,
+                        Call::Remark { .. } => CallName::Remark,
+                    }
+                }
+
+                /// The index of this call, as it is encoded
+                pub fn call_index(&self) -> u8 {
+                    self.call_name() as u8
+                }
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_expand_no_std() {
+        let expanded = PalletCallConfig::default()
+            .no_std()
+            .module("balances")
+            .push_std_derive(syn::parse_quote!(serde::Serialize))
+            .include_calls(["remark"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            pub mod balances {
+                #[allow(unused_imports)]
+                use alloc::vec::Vec;
+                #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+                #[cfg_attr(feature = "std", derive(serde::Serialize))]
+                pub enum Call {
+                    #[codec(index = 2)]
+                    Remark(Vec<u8>)
+                }
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+
+        let err = PalletCallConfig::default()
+            .no_std()
+            .push_derive(syn::parse_quote!(std::hash::Hash))
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap_err();
+        assert!(err.to_string().contains("no_std"));
+    }
+
+    #[test]
+    fn can_apply_profile() {
+        let expanded = PalletCallConfig::default()
+            .push_derive(syn::parse_quote!(Debug))
+            .profile(Profile::Fuzz)
+            .base_derives(vec![syn::parse_quote!(Clone)])
+            .include_calls(["remark"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, Debug)]
+            #[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+            pub enum Call {
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn resolves_profile_codec_crate_on_expansion() {
+        let expanded = PalletCallConfig::default()
+            .profile(Profile::NoStd)
+            .codec_crate("::parity_scale_codec")
+            .base_derives(vec![syn::parse_quote!(Clone)])
+            .include_calls(["remark"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[allow(unused_imports)]
+            use alloc::vec::Vec;
+            #[derive(Clone, ::parity_scale_codec::MaxEncodedLen, scale_info::TypeInfo)]
+            pub enum Call {
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_omit_codec() {
+        let expanded = PalletCallConfig::default()
+            .without_codec()
+            .include_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, Balance)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_hide_docs() {
+        let expanded = PalletCallConfig::default()
+            .doc_hidden()
+            .allow_missing_docs()
+            .hidden_calls(["set_*"])
+            .exclude_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            #[doc(hidden)]
+            #[allow(missing_docs)]
+            pub enum Call<Source, Balance> {
+                #[doc(hidden)]
+                #[codec(index = 1)]
+                SetBalance(Source, #[codec(compact)] Balance),
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_carry_call_docs() {
+        let mut config = PalletCallConfig::default().call_docs();
+        config.options_mut().parameter_style = ParameterStyle::Named;
+        let expanded = config
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                /// Transfer some balance
+                ///
+                /// - `dest`: The recipient
+                transfer { dest: T::AccountId, #[codec(compact)] value: T::Balance },
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<AccountId, Balance> {
+                #[doc = " Transfer some balance"]
+                #[doc = ""]
+                #[doc = " - `dest`: The recipient"]
+                Transfer {
+                    #[doc = " The recipient"]
+                    dest: AccountId,
+                    #[codec(compact)]
+                    value: Balance
+                }
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_collect_all_errors() {
+        let err = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::Balance, <T as Config>::Balance),
+                batch([T::Hash; 32], (T::AccountId, u8)),
+                remark(fn(T::Hash)),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap_err();
+
+        let errors = err.into_errors();
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(&errors[0], Error::Collision { name, .. } if name == "Balance"));
+        assert!(
+            matches!(&errors[1], Error::UnsupportedType { call, ty, .. } if call == "batch" && ty == "[T::Hash; 32]")
+        );
+        assert!(matches!(&errors[3], Error::UnsupportedType { call, .. } if call == "remark"));
+    }
+
+    #[test]
+    fn can_report_warnings() {
+        let call = PalletCallConfig::default()
+            .exclude_calls(["remark"])
+            .deny_attributes(["codec"])
+            .parse(CALL)
+            .unwrap();
+        let (_, warnings) = call.expand_with_report().unwrap();
+
+        let kinds = warnings.iter().map(|w| w.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                WarningKind::SkippedCall,
+                WarningKind::DroppedAttribute,
+                WarningKind::DroppedDocs,
+                WarningKind::DroppedAttribute
+            ]
+        );
+        assert_eq!(
+            warnings[1].to_string(),
+            "Attribute `codec` is removed in call `transfer`"
+        );
+
+        let err = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .deny_warnings()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap_err();
+        assert_eq!(err.into_errors().len(), 2);
+    }
+
+    #[test]
+    fn can_post_process() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .module("balances")
+            .post_process(|tokens| {
+                quote! {
+                    #tokens
+                    impl Marker for Call {}
+                }
+            })
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            pub mod balances {
+                #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+                pub enum Call {
+                    #[codec(index = 2)]
+                    Remark(Vec<u8>)
+                }
+                impl Marker for Call {}
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_insert_transforms() {
+        let expanded = PalletCallConfig::default()
+            .transform_after(
+                Pass::SkipCalls,
+                |_: &PalletCallConfig, call: &mut ExpandedCall| -> Result<()> {
+                    call.variants.retain(|v| v.call_name != "transfer");
+                    Ok(())
+                },
+            )
+            .transform(
+                |_: &PalletCallConfig, call: &mut ExpandedCall| -> Result<()> {
+                    call.attrs.push(syn::parse_quote!(#[allow(dead_code)]));
+                    Ok(())
+                },
+            )
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            #[allow(dead_code)]
+            pub enum Call<Source, Balance> {
+                #[codec(index = 1)]
+                SetBalance(Source, #[codec(compact)] Balance),
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_expand_borrowed() {
+        let input = syn::parse_str::<syn::DeriveInput>(CALL).unwrap();
+        let generic = PalletCallConfig::default();
+        let concrete = PalletCallConfig::default().substitute("Balance", syn::parse_quote!(u128));
+
+        let borrowed = PalletCall::new(&input, &generic).expand().unwrap();
+        let owned = generic.clone().parse(CALL).unwrap().expand().unwrap();
+        assert_eq!(borrowed.to_string(), owned.to_string());
+
+        let substituted = PalletCall::new(&input, &concrete).expand().unwrap();
+        assert_ne!(substituted.to_string(), borrowed.to_string());
+    }
+
+    #[test]
+    fn can_parse_item() {
+        let item = syn::parse_str::<syn::Item>(CALL).unwrap();
+        let from_item = PalletCallConfig::default()
+            .parse_item(item)
+            .unwrap()
+            .expand()
+            .unwrap();
+        let from_str = PalletCallConfig::default()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        assert_eq!(from_item.to_string(), from_str.to_string());
+
+        let item = syn::parse_str::<syn::Item>("pub struct Call;").unwrap();
+        assert!(PalletCallConfig::default().parse_item(item).is_err());
+    }
+
+    #[test]
+    fn can_export_abi() {
+        let call = PalletCallConfig::default().parse(CALL).unwrap();
+        let abi = call.abi().unwrap();
+        assert_eq!(abi.calls.len(), 3);
+        assert_eq!(abi.calls[0].name, "transfer");
+        assert_eq!(abi.calls[0].args.len(), 2);
+
+        // the ABI doesn't depend on the docs
+        let undocumented = PalletCallConfig::default()
+            .parse(CALL.replace("Transfer some balance", "Moves funds"))
+            .unwrap();
+        assert_eq!(
+            call.to_abi_json().unwrap(),
+            undocumented.to_abi_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn can_introspect_calls() {
+        let calls = PalletCallConfig::default()
+            .exclude_calls(["remark"])
+            .parse(CALL)
+            .unwrap()
+            .calls()
+            .unwrap();
+
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].name, "transfer");
+        assert_eq!(calls[0].docs, vec!["Transfer some balance"]);
+        assert_eq!(
+            calls[0].args,
+            vec![
+                ArgInfo {
+                    name: None,
+                    ty: "<T::Lookup as StaticLookup>::Source".to_string(),
+                    compact: false,
+                },
+                ArgInfo {
+                    name: None,
+                    ty: "T::Balance".to_string(),
+                    compact: true,
+                },
+            ]
+        );
+        assert_eq!(calls[2].name, "remark");
+        assert_eq!(calls[2].index, 2);
+    }
+
+    #[test]
+    fn signature_hash_ignores_docs() {
+        let hash = PalletCallConfig::default()
+            .parse(CALL)
+            .unwrap()
+            .signature_hash()
+            .unwrap();
+        let documented = PalletCallConfig::default()
+            .keep_comments::<()>()
+            .push_derive(syn::parse_quote!(Debug))
+            .parse(CALL)
+            .unwrap()
+            .signature_hash()
+            .unwrap();
+        assert_eq!(hash, documented);
+
+        let changed = PalletCallConfig::default()
+            .parse(CALL.replace("remark(Vec<u8>)", "remark(Vec<u16>)"))
+            .unwrap()
+            .signature_hash()
+            .unwrap();
+        assert_ne!(hash, changed);
+    }
+
+    #[test]
+    fn can_export_json() {
+        let call = PalletCallConfig::default()
+            .include_calls(["transfer"])
+            .parse(CALL)
+            .unwrap();
+        let json = call.to_json().unwrap();
+        let metadata: CallMetadata = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(metadata, call.metadata().unwrap());
+        assert_eq!(metadata.name, "Call");
+        assert_eq!(metadata.generics["Balance"], "T::Balance");
+        assert_eq!(
+            metadata.generics["Source"],
+            "<T::Lookup as StaticLookup>::Source"
+        );
+        assert_eq!(metadata.calls.len(), 1);
+        assert!(json.contains(r#""name": "transfer""#));
+    }
+
+    #[test]
+    fn can_create_json_schema() {
+        let call = PalletCallConfig::default()
+            .include_calls(["transfer", "remark"])
+            .substitute("Source", syn::parse_quote!(u64));
+        assert!(call.clone().parse(CALL).unwrap().json_schema().is_err());
+
+        let schema = call
+            .substitute("Balance", syn::parse_quote!(u128))
+            .parse(CALL)
+            .unwrap()
+            .json_schema()
+            .unwrap();
+        assert_eq!(schema["title"], "Call");
+        let variants = schema["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 2);
+        assert_eq!(
+            variants[0]["properties"]["Transfer"]["minItems"],
+            serde_json::json!(2)
+        );
+        assert_eq!(
+            variants[1]["properties"]["Remark"]["type"],
+            serde_json::json!("array")
+        );
+    }
+
+    #[test]
+    fn can_emit_typescript_definitions() {
+        let definitions = PalletCallConfig::default()
+            .include_calls(["transfer", "remark"])
+            .substitute("Source", syn::parse_quote!(u64))
+            .substitute("Balance", syn::parse_quote!(u128))
+            .parse(CALL)
+            .unwrap()
+            .to_typescript()
+            .unwrap();
+        assert_eq!(
+            definitions,
+            "export type Call =\n  | { Transfer: [number, number] }\n  | { Remark: number[] };\n"
+        );
+    }
+
+    #[test]
+    fn can_emit_type_aliases() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer"])
+            .type_alias("Balance", syn::parse_quote!(u128))
+            .type_alias("Source", syn::parse_quote!(sp_runtime::AccountId32))
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance)
+            }
+            pub mod types {
+                pub type Balance = u128;
+                pub type Source = sp_runtime::AccountId32;
+                pub type Call = super::Call<Source, Balance>;
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_keep_config_generics() {
+        let expanded = PalletCallConfig::default()
+            .keep_config_generics()
+            .exclude_calls(["set_balance"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<T: Config> {
+                #[codec(index = 0)]
+                Transfer(<T::Lookup as StaticLookup>::Source, #[codec(compact)] T::Balance),
+                #[codec(index = 2)]
+                Remark(Vec<u8>),
+                #[doc(hidden)]
+                #[codec(skip)]
+                __Ignore(core::marker::PhantomData<(T,)>, core::convert::Infallible)
+            }
+            #[allow(type_alias_bounds)]
+            pub type SourceOf<T: Config> = <T::Lookup as StaticLookup>::Source;
+            #[allow(type_alias_bounds)]
+            pub type BalanceOf<T: Config> = T::Balance;
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_concrete_call() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer"])
+            .concrete(
+                "RuntimeCall",
+                vec![
+                    ("Balance", syn::parse_quote!(u128)),
+                    ("Source", syn::parse_quote!(sp_runtime::AccountId32)),
+                ],
+            )
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance)
+            }
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum RuntimeCall {
+                Transfer(sp_runtime::AccountId32, #[codec(compact)] u128)
+            }
+            impl From<Call<sp_runtime::AccountId32, u128> > for RuntimeCall {
+                fn from(call: Call<sp_runtime::AccountId32, u128>) -> Self {
+                    match call {
+                        Call::Transfer(f0, f1) => RuntimeCall::Transfer(f0, f1),
+                    }
+                }
+            }
+            impl From<RuntimeCall> for Call<sp_runtime::AccountId32, u128> {
+                fn from(call: RuntimeCall) -> Self {
+                    match call {
+                        RuntimeCall::Transfer(f0, f1) => Call::Transfer(f0, f1),
+                    }
+                }
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+
+        let err = PalletCallConfig::default()
+            .concrete("RuntimeCall", vec![("Balance", syn::parse_quote!(u128))])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+    }
+
+    #[test]
+    fn can_use_raw_identifiers() {
+        let expanded = PalletCallConfig::default()
+            .variant_name(|name| name.to_string())
+            .override_call(
+                "move",
+                CallOverride {
+                    parameter_style: Some(ParameterStyle::Named),
+                    ..Default::default()
+                },
+            )
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                r#type(T::r#type, u8),
+                r#move { r#ref: T::Balance },
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Balance, r#type> {
+                r#type(r#type, u8),
+                r#move { r#ref: Balance }
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_detect_variant_name_collisions() {
+        let err = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                set_key(T::AccountId),
+                set__key(T::AccountId),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap_err();
+        match err {
+            Error::Collision {
+                name, originals, ..
+            } => {
+                assert_eq!(name, "SetKey");
+                assert_eq!(originals, vec!["set_key", "set__key"]);
+            }
+            err => panic!("Unexpected error {:?}", err),
+        }
+    }
+
+    #[test]
+    fn can_parse_expanded_crate() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .parse_expanded(
+                r#"
+            pub mod weights {
+                pub enum Call {}
+            }
+            pub mod pallet {
+                pub struct Pallet<T>(PhantomData<T>);
+                pub enum Call<T: Config> {
+                    remark(Vec<u8>),
+                }
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call {
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_scale_value_conversions() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .scale_value()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap()
+            .to_string();
+
+        let to_value = quote! {
+            Call::Remark(f0) => scale_value::Value::unnamed_variant(
+                "remark",
+                vec![scale_value::serde::to_value(f0)?],
+            )
+        };
+        let from_value = quote! {
+            "remark" => {
+                let f0 = scale_value::serde::from_value(
+                    values.next().ok_or_else(|| missing("remark"))?,
+                )?;
+                Call::Remark(f0)
+            }
+        };
+        assert!(expanded.contains("# [cfg (feature = \"scale-value\")]"));
+        assert!(expanded.contains(&to_value.to_string()));
+        assert!(expanded.contains(&from_value.to_string()));
+    }
+
+    #[test]
+    fn can_emit_call_hashes() {
+        let expanded = PalletCallConfig::default()
+            .name("SystemCall")
+            .include_calls(["remark"])
+            .call_hashes("System")
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let hash = blake2_256(b"System::remark");
+        let bytes = hash.iter().map(|b| Literal::u8_unsuffixed(*b));
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum SystemCall {
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+            #[doc = " The `blake2_256` hashes of `System::<call>` for every call, by call name"]
+            pub const SYSTEM_CALL_HASHES: &[(&str, [u8; 32])] = &[("remark", [#( #bytes ),*])];
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_calls_table() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer"])
+            .calls_table()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap()
+            .to_string();
+
+        let expected = quote! {
+            pub static CALL_CALLS: &[CallMeta] = &[CallMeta {
+                name: "transfer",
+                index: 0,
+                arg_names: &["", ""],
+                arg_types: &["<T::Lookup as StaticLookup>::Source", "T::Balance"],
+                docs: "Transfer some balance",
+            }];
+        };
+        assert!(expanded.contains("pub struct CallMeta"));
+        assert!(expanded.ends_with(&expected.to_string()));
+    }
+
+    #[test]
+    fn can_emit_for_each_call_macro() {
+        let expanded = PalletCallConfig::default()
+            .name("BalancesCall")
+            .for_each_call()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap()
+            .to_string();
+
+        let calls = quote! {
+            __for_each_balances_call!(Transfer, (Source, Balance));
+            __for_each_balances_call!(SetBalance, (Source, Balance));
+            __for_each_balances_call!(Remark, (Vec<u8>));
+        };
+        assert!(expanded.contains("# [macro_export] macro_rules ! for_each_balances_call"));
+        assert!(expanded.contains(&calls.to_string()));
+
+        let expanded = PalletCallConfig::default()
+            .visibility("pub(crate)")
+            .for_each_call()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap()
+            .to_string();
+        assert!(!expanded.contains("macro_export"));
+        assert!(expanded.contains("pub (crate) use for_each_call ;"));
+    }
+
+    #[test]
+    fn can_replace_base_derives() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .base_derives(vec![
+                syn::parse_quote!(Clone),
+                syn::parse_quote!(codec::Encode),
+            ])
+            .push_derive(syn::parse_quote!(Debug))
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, codec::Encode, Debug)]
+            pub enum Call {
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_derive_decode_with_mem_tracking() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .codec_crate("parity_scale_codec")
+            .decode_with_mem_tracking()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(
+                Clone,
+                PartialEq,
+                Eq,
+                parity_scale_codec::Encode,
+                parity_scale_codec::Decode,
+                parity_scale_codec::DecodeWithMemTracking,
+            )]
+            pub enum Call {
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_configure_codec_bounds() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer"])
+            .encode_bound(vec![syn::parse_quote!(Balance: codec::HasCompact)])
+            .decode_bound(Vec::new())
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            #[codec(encode_bound(Balance: codec::HasCompact))]
+            #[codec(decode_bound())]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_drop_skipped_fields() {
+        let expanded = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                remark(Vec<u8>, #[codec(skip)] PhantomData<T>),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call {
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_keep_skipped_variants() {
+        let expanded = PalletCallConfig::default()
+            .keep_skipped_variants()
+            .include_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance),
+                #[doc(hidden)]
+                #[codec(skip)]
+                __Ignore(core::marker::PhantomData<(Source, Balance,)>, core::convert::Infallible)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+
+        let expanded = PalletCallConfig::default()
+            .keep_skipped_variants()
+            .without_codec()
+            .include_calls(["transfer"])
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, Balance),
+                #[doc(hidden)]
+                __Ignore(core::marker::PhantomData<(Source, Balance,)>, core::convert::Infallible)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_unit_variants() {
+        let expanded = PalletCallConfig::default()
+            .unit_variants()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                kill(),
+                remark(Vec<u8>),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call {
+                Kill,
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_handle_default_type_params() {
+        const INSTANCE_CALL: &str = r#"
+            pub enum Call<T: Config<I>, I: 'static = ()> {
+                #[doc(hidden)]
+                #[codec(skip)]
+                __Ignore(PhantomData<(T, I)>, Never),
+                transfer(<T as Config<I> >::Balance),
+            }
+        "#;
+
+        let erased = PalletCallConfig::default()
+            .parse(INSTANCE_CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Balance> {
+                Transfer(Balance)
+            }
+        };
+        assert_eq!(erased.to_string(), expected.to_string());
+
+        let kept = PalletCallConfig::default()
+            .keep_config_generics()
+            .parse(INSTANCE_CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<T: Config<I>, I: 'static = ()> {
+                Transfer(<T as Config<I> >::Balance),
+                #[doc(hidden)]
+                #[codec(skip)]
+                __Ignore(core::marker::PhantomData<(T, I,)>, core::convert::Infallible)
+            }
+            #[allow(type_alias_bounds)]
+            pub type BalanceOf<T: Config<I>, I: 'static = ()> = <T as Config<I> >::Balance;
+        };
+        assert_eq!(kept.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_keep_const_generic_types() {
+        let expanded = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                set_code([u8; 32], BoundedVec<u8, ConstU32<32>>, T::Hash),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Hash> {
+                SetCode([u8; 32], BoundedVec<u8, ConstU32<32> >, Hash)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_propagate_lifetimes() {
+        let expanded = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                remark(&'a [u8], T::Hash),
+                remark_static(&'static [u8]),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<'a, Hash> {
+                Remark(&'a [u8], Hash),
+                RemarkStatic(&'static [u8])
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+
+        let err = PalletCallConfig::default()
+            .parse("pub enum Call<T: Config> { remark(Vec<u8>, &[u8]) }")
+            .unwrap()
+            .expand()
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::ElidedLifetime { call, field, .. } if call == "remark" && field == "1")
+        );
+    }
+
+    #[test]
+    fn can_emit_references_to_config_types() {
+        const REFERENCE_CALL: &str = r#"
+            pub enum Call<T: Config> {
+                transfer(&T::AccountId, &[T::Hash]),
+            }
+        "#;
+
+        let borrowed = PalletCallConfig::default()
+            .parse(REFERENCE_CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<'a, AccountId, Hash> {
+                Transfer(&'a AccountId, &'a [Hash])
+            }
+        };
+        assert_eq!(borrowed.to_string(), expected.to_string());
+
+        let owned = PalletCallConfig::default()
+            .reference_mode(ReferenceMode::Owned)
+            .parse(REFERENCE_CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<AccountId, Hash> {
+                Transfer(AccountId, Vec<Hash>)
+            }
+        };
+        assert_eq!(owned.to_string(), expected.to_string());
+
+        let substituted = PalletCallConfig::default()
+            .reference_mode(ReferenceMode::Owned)
+            .substitute("Hash", syn::parse_quote!([u8; 32]))
+            .parse(REFERENCE_CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<AccountId> {
+                Transfer(AccountId, Vec<[u8; 32]>)
+            }
+        };
+        assert_eq!(substituted.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_rewrite_types() {
+        let expanded = PalletCallConfig::default()
+            .rewrite_type(|ty| {
+                if ty == &syn::parse_quote!(Vec<u8>) {
+                    Some(syn::parse_quote!(Bytes))
+                } else if ty == &syn::parse_quote!(sp_core::H256) {
+                    Some(syn::parse_quote!([u8; 32]))
+                } else {
+                    None
+                }
+            })
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                remark(Vec<u8>, sp_core::H256, T::Hash),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Hash> {
+                Remark(Bytes, [u8; 32], Hash)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_map_type_paths() {
+        let mut config = PalletCallConfig::default();
+        config.options_mut().type_paths.insert(
+            "sp_runtime::MultiAddress".to_string(),
+            "my_types::MultiAddress".to_string(),
+        );
+        config
+            .options_mut()
+            .type_paths
+            .insert("sp_core::H256".to_string(), "my_types::H256".to_string());
+        let expanded = config
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(sp_runtime::MultiAddress<AccountId32, ()>, Vec<::sp_core::H256>, T::Balance),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Balance> {
+                Transfer(my_types::MultiAddress<AccountId32, ()>, Vec<my_types::H256>, Balance)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_lookup_helpers() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer"])
+            .lookup_helpers()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance)
+            }
+
+            impl<Source, Balance> Call<Source, Balance>
+            where
+                Source: From<sp_runtime::MultiAddress<sp_runtime::AccountId32, ()> >,
+            {
+                #[doc = " Creates a `transfer` call, lookup sources are created from account ids"]
+                pub fn transfer_with_accounts(f0: sp_runtime::AccountId32, f1: Balance) -> Self {
+                    Call::Transfer(sp_runtime::MultiAddress::Id(f0).into(), f1)
+                }
+            }
+
+            /// Parses an ss58 address into the `MultiAddress` of a lookup source
+            #[cfg(feature = "std")]
+            pub fn multi_address_from_ss58(
+                address: &str,
+            ) -> Result<sp_runtime::MultiAddress<sp_runtime::AccountId32, ()>, sp_core::crypto::PublicError> {
+                use sp_core::crypto::Ss58Codec;
+                sp_runtime::AccountId32::from_ss58check(address).map(sp_runtime::MultiAddress::Id)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_types_trait() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer"])
+            .types_trait()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                Transfer(Source, #[codec(compact)] Balance)
+            }
+
+            #[doc = " The types of the generics of [`Call`]"]
+            pub trait CallTypes {
+                type Source;
+                type Balance;
+            }
+
+            #[doc = " [`Call`] with the types of `C`"]
+            pub type CallFor<C> = Call< <C as CallTypes>::Source, <C as CallTypes>::Balance>;
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_trait_constructors() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer", "remark"])
+            .trait_constructors()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<Source, Balance> {
+                #[codec(index = 0)]
+                Transfer(Source, #[codec(compact)] Balance),
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+
+            #[doc = " The types of the generics of [`Call`]"]
+            pub trait CallTypes {
+                type Source;
+                type Balance;
+            }
+
+            #[doc = " [`Call`] with the types of `C`"]
+            pub type CallFor<C> = Call< <C as CallTypes>::Source, <C as CallTypes>::Balance>;
+
+            #[doc = " Creates a `transfer` call"]
+            pub fn transfer<C: CallTypes>(
+                f0: <C as CallTypes>::Source,
+                f1: <C as CallTypes>::Balance
+            ) -> CallFor<C> {
+                Call::Transfer(f0, f1)
+            }
+
+            #[doc = " Creates a `remark` call"]
+            pub fn remark<C: CallTypes>(f0: Vec<u8>) -> CallFor<C> {
+                Call::Remark(f0)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn can_emit_constructor_examples() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .trait_constructors()
+            .constructor_examples()
+            .codec_crate("::parity_scale_codec")
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[doc = " Creates a `remark` call"]
+            #[doc = ""]
+            #[doc = " # Example"]
+            #[doc = ""]
+            #[doc = " ```ignore"]
+            #[doc = " let call = remark(Default::default());"]
+            #[doc = " let encoded = ::parity_scale_codec::Encode::encode(&call);"]
+            #[doc = " ```"]
+            pub fn remark(f0: Vec<u8>) -> Call {
+                Call::Remark(f0)
+            }
+        };
+        assert!(expanded.to_string().ends_with(&expected.to_string()));
+    }
+
+    #[test]
+    fn can_emit_call_filter() {
+        let expanded = PalletCallConfig::default()
+            .call_filter(Vec::<String>::new(), vec!["set_*"])
+            .call_filter_contains("runtime")
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[doc = " Matches the allowed calls of [`Call`]"]
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+            pub struct CallFilter;
+
+            impl CallFilter {
+                /// Whether the call is allowed
+                pub fn matches<Source, Balance>(&self, call: &Call<Source, Balance>) -> bool {
+                    #[allow(unreachable_patterns)]
+                    match call {
+                        Call::Transfer { .. } => true,
+                        Call::Remark { .. } => true,
+                        _ => false,
+                    }
+                }
+            }
+
+            #[cfg(feature = "runtime")]
+            impl<Source, Balance> frame_support::traits::Contains<Call<Source, Balance> > for CallFilter {
+                fn contains(call: &Call<Source, Balance>) -> bool {
+                    CallFilter.matches(call)
+                }
+            }
+        };
+        assert!(expanded.to_string().contains(&expected.to_string()));
+
+        let expanded = PalletCallConfig::default()
+            .call_filter(vec!["transfer"], Vec::<String>::new())
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        assert!(!expanded.to_string().contains("Contains"));
+    }
+
+    #[test]
+    fn can_classify_required_origins() {
+        let dir = test_dir("origin");
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("lib.rs");
+        std::fs::write(
+            &source,
+            r#"
+            #[pallet::call]
+            impl<T: Config> Pallet<T> {
+                pub fn transfer(origin: OriginFor<T>) -> DispatchResult {
+                    ensure_signed(origin)?;
+                    Ok(())
+                }
+                pub fn set_balance(origin: OriginFor<T>) -> DispatchResult {
+                    ensure_root(origin)?;
+                    Ok(())
+                }
+            }
+        "#,
+        )
+        .unwrap();
+
+        let call = PalletCallConfig::default()
+            .name("BalancesCall")
+            .origin_source(&source)
+            .required_origin()
+            .parse(CALL)
+            .unwrap();
+        let metadata = call.metadata().unwrap();
+        assert_eq!(metadata.calls[0].origin, Some(OriginKind::Signed));
+        assert_eq!(metadata.calls[1].origin, Some(OriginKind::Root));
+        assert_eq!(metadata.calls[2].origin, None);
+
+        let expanded = call.expand().unwrap().to_string();
+        let arms = quote! {
+            match self {
+                BalancesCall::Transfer { .. } => BalancesCallOrigin::Signed,
+                BalancesCall::SetBalance { .. } => BalancesCallOrigin::Root,
+                BalancesCall::Remark { .. } => BalancesCallOrigin::Unknown,
+            }
+        };
+        assert!(expanded.contains(&arms.to_string()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn can_attach_static_weights() {
+        let dir = test_dir("weights");
+        std::fs::create_dir_all(&dir).unwrap();
+        let weights = dir.join("weights.rs");
+        std::fs::write(
+            &weights,
+            r#"
+            impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+                fn transfer() -> Weight {
+                    Weight::from_parts(38_480_000, 3593)
+                        .saturating_add(T::DbWeight::get().reads(1_u64))
+                        .saturating_add(T::DbWeight::get().writes(1_u64))
+                }
+            }
+        "#,
+        )
+        .unwrap();
+
+        let call = PalletCallConfig::default()
+            .name("BalancesCall")
+            .include_calls(["transfer", "remark"])
+            .weights_source(&weights)
+            .static_weights()
+            .parse(CALL)
+            .unwrap();
+        let metadata = call.metadata().unwrap();
+        assert_eq!(metadata.calls[0].weight.unwrap().ref_time, 38_480_000);
+        assert_eq!(metadata.calls[1].weight, None);
+
+        let expanded = call.expand().unwrap().to_string();
+        let arms = quote! {
+            match self {
+                BalancesCall::Transfer { .. } => Some(BalancesCallWeight {
+                    ref_time: 38480000u64,
+                    proof_size: 3593u64,
+                    reads: 1u64,
+                    writes: 1u64,
+                }),
+                BalancesCall::Remark { .. } => None,
+            }
+        };
+        assert!(expanded.contains(&arms.to_string()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn can_emit_decode_fixture_tests() {
+        let dir = test_dir("fixtures");
+        std::fs::create_dir_all(&dir).unwrap();
+        let fixtures = dir.join("fixtures.json");
+        std::fs::write(&fixtures, r#"{ "remark": "0x02080102" }"#).unwrap();
+
+        let expanded = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .concrete("RuntimeCall", Vec::<(&str, Type)>::new())
+            .decode_fixtures(&fixtures)
+            .codec_crate("::parity_scale_codec")
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap()
+            .to_string();
+        let concrete = quote! {
+            pub enum RuntimeCall {
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+        };
+        let expected = quote! {
+            #[cfg(test)]
+            mod decode_fixtures {
+                use super::*;
+
+                #[test]
+                fn decodes_remark() {
+                    let mut input: &[u8] = &[2u8, 8u8, 1u8, 2u8];
+                    let call = <RuntimeCall as ::parity_scale_codec::Decode>::decode(&mut input)
+                        .expect("failed to decode `remark`");
+                    assert!(input.is_empty(), "trailing bytes after the call");
+                    assert!(matches!(call, RuntimeCall::Remark { .. }));
+                }
+            }
+        };
+        assert!(expanded.contains(&concrete.to_string()));
+        assert!(expanded.ends_with(&expected.to_string()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// The expansion of `tests/fixtures/remark.json`, compiled with the tests
+    /// so that its decode tests run
+    #[allow(dead_code)]
+    mod emitted_decode_fixtures {
+        include!("../tests/fixtures/decode_fixtures.rs");
+    }
+
+    #[test]
+    fn emitted_decode_fixtures_are_up_to_date() {
+        let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/remark.json");
+        let expanded = PalletCallConfig::default()
+            .include_calls(["remark"])
+            .concrete("RuntimeCall", Vec::<(&str, Type)>::new())
+            .decode_fixtures(fixtures)
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap();
+        let emitted =
+            syn::parse_file(include_str!("../tests/fixtures/decode_fixtures.rs")).unwrap();
+        let expanded = syn::parse2::<syn::File>(expanded).unwrap();
+        assert_eq!(quote!(#expanded).to_string(), quote!(#emitted).to_string());
+    }
+
+    #[test]
+    fn can_emit_encoded_examples() {
+        let expanded = PalletCallConfig::default()
+            .include_calls(["transfer", "remark"])
+            .concrete(
+                "RuntimeCall",
+                vec![
+                    ("Balance", syn::parse_quote!(u128)),
+                    ("Source", syn::parse_quote!(sp_runtime::AccountId32)),
+                ],
+            )
+            .encoded_examples()
+            .example_args("remark", vec![syn::parse_quote!(vec![1, 2])])
+            .codec_crate("::parity_scale_codec")
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap()
+            .to_string();
+        let expected = quote! {
+            fn examples() -> Vec<(&'static str, Self)> {
+                vec![
+                    ("transfer", RuntimeCall::Transfer(Default::default(), Default::default())),
+                    ("remark", RuntimeCall::Remark(vec![1, 2]))
+                ]
+            }
+        };
+        assert!(expanded.contains(&expected.to_string()));
+        let encode = quote! { (name, ::parity_scale_codec::Encode::encode(&call)) };
+        assert!(expanded.contains(&encode.to_string()));
+
+        let err = PalletCallConfig::default()
+            .encoded_examples()
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+    }
+
+    #[test]
+    fn can_emit_size_hint() {
+        let expanded = PalletCallConfig::default()
+            .size_hint()
+            .codec_crate("::parity_scale_codec")
+            .parse(CALL)
+            .unwrap()
+            .expand()
+            .unwrap()
+            .to_string();
+        let expected = quote! {
+            impl<Source, Balance> Call<Source, Balance> {
+                /// The estimated size of the encoded call, in bytes
+                pub fn encoded_size_hint(&self) -> usize
+                where
+                    Self: ::parity_scale_codec::Encode,
+                {
+                    ::parity_scale_codec::Encode::size_hint(self)
+                }
+            }
+        };
+        assert!(expanded.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn can_render_provenance_header() {
+        let call = PalletCallConfig::default()
+            .module("balances")
+            .provenance_header()
+            .parse(CALL)
+            .unwrap();
+        let header = call.header().unwrap();
+        let lines = header.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            format!(
+                "// Generated by substrate-pallet-call-extract {}, do not edit manually.",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+        assert_eq!(lines[1], "//");
+        assert_eq!(lines[2], "// pallet: balances");
+        assert!(lines[3].starts_with("// input: blake2-256 "));
+        assert!(lines[4].contains(r#""module":"balances""#));
+        assert!(!lines[4].contains("header"));
+        assert!(call.to_source().unwrap().starts_with(&header));
+    }
+
+    #[test]
+    fn can_prepend_preamble() {
+        let preamble = "// SPDX-License-Identifier: Apache-2.0\n\n#![allow(clippy::all)]";
+        let source = PalletCallConfig::default()
+            .preamble(preamble)
+            .provenance_header()
+            .parse(CALL)
+            .unwrap()
+            .to_source()
+            .unwrap();
+        assert!(source.starts_with(&format!("{}\n// Generated by", preamble)));
+
+        let err = PalletCallConfig::default()
+            .preamble("#![allow(")
+            .parse(CALL)
+            .unwrap()
+            .to_source()
+            .unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+    }
+
+    #[test]
+    fn can_skip_invalid_calls() {
+        let (expanded, warnings) = PalletCallConfig::default()
+            .lenient()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, T::Balance),
+                batch(T::Balance, [T::Hash; 32]),
+                remark(Vec<u8>),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand_with_report()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call<AccountId, Balance> {
+                #[codec(index = 0)]
+                Transfer(AccountId, Balance),
+                #[codec(index = 2)]
+                Remark(Vec<u8>)
+            }
+        };
+        assert_eq!(expanded.to_string(), expected.to_string());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::InvalidCall);
+        assert_eq!(warnings[0].call, "batch");
+    }
+
+    #[test]
+    fn can_use_opaque_type() {
+        let expanded = PalletCallConfig::default()
+            .opaque_type(syn::parse_quote!(Vec<u8>))
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                batch([T::Hash; 32], (T::AccountId, u8)),
+            }
+        "#,
+            )
+            .unwrap()
+            .expand()
+            .unwrap();
+
+        let expected = quote! {
+            #[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+            pub enum Call {
+                Batch(
+                    #[doc = " Opaque: the original type `[T::Hash; 32]` is not supported"]
+                    Vec<u8>,
+                    #[doc = " Opaque: the original type `(T::AccountId, u8)` is not supported"]
+                    Vec<u8>
+                )
+            }
+        };
         assert_eq!(expanded.to_string(), expected.to_string());
     }
 }
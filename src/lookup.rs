@@ -0,0 +1,134 @@
+//! Emits constructors that take account ids for lookup source parameters
+
+use crate::value::bindings;
+use crate::{parse_ident, ExpandedCall, Result};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Fields, Type, TypePath, Variant, Visibility};
+
+/// The `MultiAddress` a lookup source is created from
+const MULTI_ADDRESS: &str = "sp_runtime::MultiAddress<sp_runtime::AccountId32, ()>";
+
+/// Whether the type is the lookup source of an account, like
+/// `<T::Lookup as StaticLookup>::Source` or `AccountIdLookupOf<T>`
+pub(crate) fn is_lookup_source(ty: &TypePath) -> bool {
+    let last = match ty.path.segments.last() {
+        Some(last) => last,
+        None => return false,
+    };
+    match ty.qself.as_ref() {
+        Some(_) => {
+            last.ident == "Source"
+                && ty
+                    .path
+                    .segments
+                    .iter()
+                    .any(|segment| segment.ident == "StaticLookup")
+        }
+        None => last.ident == "AccountIdLookupOf",
+    }
+}
+
+/// Emits a `<call>_with_accounts` constructor for every call with a lookup
+/// source parameter, which takes an `AccountId32` instead, and a helper to
+/// parse an ss58 address
+///
+/// The `variants` are the variants of the generic enum, as they are emitted.
+pub(crate) fn emit(
+    call: &ExpandedCall,
+    variants: &[Variant],
+    impl_generics: &TokenStream,
+    ty_generics: &TokenStream,
+    vis: &Visibility,
+) -> Result<TokenStream> {
+    let sources = call
+        .generics
+        .iter()
+        .filter(|(ty, _)| is_lookup_source(&call.types[ty.as_str()]))
+        .map(|(_, generic)| parse_ident(generic))
+        .collect::<syn::Result<Vec<_>>>()?;
+    if sources.is_empty() {
+        return Ok(quote! {});
+    }
+    let multi_address: Type = syn::parse_str(MULTI_ADDRESS)?;
+    let is_source = |ty: &Type| match ty {
+        Type::Path(path) => path.qself.is_none() && sources.iter().any(|s| path.path.is_ident(s)),
+        _ => false,
+    };
+
+    let name = &call.name;
+    let mut constructors = Vec::new();
+    for (v, variant) in call.variants.iter().zip(variants) {
+        let fields = match &variant.fields {
+            Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+            Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
+            Fields::Unit => continue,
+        };
+        if !fields.iter().any(|field| is_source(&field.ty)) {
+            continue;
+        }
+        let bindings = bindings(&variant.fields);
+        let mut params = Vec::with_capacity(fields.len());
+        let mut values = Vec::with_capacity(fields.len());
+        for (field, binding) in fields.iter().zip(&bindings) {
+            if is_source(&field.ty) {
+                params.push(quote! { #binding: sp_runtime::AccountId32 });
+                values.push(quote! { sp_runtime::MultiAddress::Id(#binding).into() });
+            } else {
+                let ty = &field.ty;
+                params.push(quote! { #binding: #ty });
+                values.push(quote! { #binding });
+            }
+        }
+        let ident = &variant.ident;
+        let value = match &variant.fields {
+            Fields::Named(_) => quote! { { #( #bindings: #values ),* } },
+            _ => quote! { ( #( #values ),* ) },
+        };
+        let constructor = format_ident!("{}_with_accounts", v.call_name);
+        let doc = format!(
+            " Creates a `{}` call, lookup sources are created from account ids",
+            v.call_name
+        );
+        constructors.push(quote! {
+            #[doc = #doc]
+            #vis fn #constructor( #( #params ),* ) -> Self {
+                #name::#ident #value
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics
+        where
+            #( #sources: From<#multi_address>, )*
+        {
+            #( #constructors )*
+        }
+
+        /// Parses an ss58 address into the `MultiAddress` of a lookup source
+        #[cfg(feature = "std")]
+        #vis fn multi_address_from_ss58(
+            address: &str,
+        ) -> Result<#multi_address, sp_core::crypto::PublicError> {
+            use sp_core::crypto::Ss58Codec;
+            sp_runtime::AccountId32::from_ss58check(address).map(sp_runtime::MultiAddress::Id)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_detect_lookup_sources() {
+        let is_source = |ty: &str| is_lookup_source(&syn::parse_str(ty).unwrap());
+        assert!(is_source("<T::Lookup as StaticLookup>::Source"));
+        assert!(is_source(
+            "<<T as frame_system::Config>::Lookup as sp_runtime::traits::StaticLookup>::Source"
+        ));
+        assert!(is_source("AccountIdLookupOf<T>"));
+        assert!(!is_source("T::AccountId"));
+    }
+}
@@ -0,0 +1,376 @@
+//! Declarative extraction of multiple pallets described by a manifest file
+
+use crate::{
+    output, scan_workspace, Cache, CallOptions, CallSet, PalletCall, PalletCallConfig, WriteMode,
+};
+use anyhow::Context;
+use dep_expand::Expander;
+use inflector::Inflector;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Describes a set of pallets to extract
+///
+/// A manifest can be written in TOML or JSON, for example:
+///
+/// ```toml
+/// # write all pallets into a single file, optional
+/// output = "src/calls.rs"
+/// # or write every pallet to its own file with a `mod.rs`, optional
+/// # output_dir = "src/calls"
+/// # file_name = "{module}_call.rs"
+/// # skip the expansion of unchanged pallets, optional
+/// cache = "target/call-extract"
+///
+/// # concrete types for generics shared across all pallets
+/// [types]
+/// AccountId = "sp_runtime::AccountId32"
+///
+/// [[pallet]]
+/// crate = "pallet-balances"
+/// name = "BalancesCall"
+/// derives = ["Debug"]
+/// output = "src/balances.rs"
+///
+/// [pallet.substitutions]
+/// Balance = "u128"
+/// ```
+///
+/// Relative paths are resolved relative to the directory of the manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Write all pallets into this file, each in its own module
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    /// Write every pallet to its own file in this directory, declared in a
+    /// `mod.rs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<PathBuf>,
+    /// The name of the file of a pallet in `output_dir`, `{module}` is
+    /// replaced with the module of the pallet, by default `{module}.rs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    /// Whether to emit a `prelude` module that re-exports the generated items
+    /// of all pallets, see [`CallSet::prelude`]
+    #[serde(default)]
+    pub prelude: bool,
+    /// Concrete types for generics shared across all pallets written to
+    /// `output` or `output_dir`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub types: BTreeMap<String, String>,
+    /// Cache the generated code in this directory, keyed by the hash of the
+    /// `pallet::Call` and the options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<PathBuf>,
+    /// All pallets to extract
+    #[serde(default, rename = "pallet")]
+    pub pallets: Vec<PalletManifest>,
+}
+
+/// Describes a single pallet to extract
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PalletManifest {
+    /// The name of the module the pallet is emitted in when writing to the
+    /// manifest's `output`, by default derived from `crate` or `input`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    /// The pallet crate to expand, this must be a dependency of the current
+    /// crate
+    #[serde(default, rename = "crate", skip_serializing_if = "Option::is_none")]
+    pub krate: Option<String>,
+    /// A file that contains the already expanded `pallet::Call`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<PathBuf>,
+    /// Write the generated code of this pallet to this file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    /// Whether to check that the generated code compiles before it's
+    /// written, this requires the `verify` feature
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub verify: bool,
+    /// How to expand the pallet, like `name`, `derives` or `substitutions`
+    #[serde(flatten)]
+    pub options: CallOptions,
+}
+
+impl Manifest {
+    /// Reads the manifest from a `.json` or `.toml` file
+    pub fn from_path(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+        let manifest = if path
+            .extension()
+            .map(|ext| ext == "json")
+            .unwrap_or_default()
+        {
+            serde_json::from_str(&content)?
+        } else {
+            toml::from_str(&content)?
+        };
+        Ok(manifest)
+    }
+
+    /// Extracts all pallets and writes the configured outputs
+    ///
+    /// The pallets are expanded in parallel, each on its own thread. The
+    /// results are collected in the order of the manifest, so the output does
+    /// not depend on which pallet finished first.
+    ///
+    /// Relative paths are resolved against `root`
+    pub fn run(&self, root: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.run_with_mode(root, WriteMode::IfChanged)
+    }
+
+    /// A manifest with a pallet for every pallet crate of the workspace at
+    /// `root`, see [`scan_workspace`]
+    pub fn from_workspace(root: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let pallets = scan_workspace(root)?
+            .into_iter()
+            .map(|krate| PalletManifest {
+                krate: Some(krate.name),
+                ..Default::default()
+            })
+            .collect();
+        Ok(Manifest {
+            pallets,
+            ..Default::default()
+        })
+    }
+
+    /// Extracts all pallets like `run` and handles existing outputs according
+    /// to the `WriteMode`
+    ///
+    /// With `WriteMode::Check` nothing is written and this fails if any
+    /// output is not up to date.
+    pub fn run_with_mode(&self, root: impl AsRef<Path>, mode: WriteMode) -> anyhow::Result<()> {
+        let root = root.as_ref();
+        let cache = self.cache.as_ref().map(|dir| Cache::new(root.join(dir)));
+        // validate all modules first, so no thread is left running on error
+        let modules = self
+            .pallets
+            .iter()
+            .map(PalletManifest::module)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        // the parsed calls can't be sent across threads, so each thread
+        // returns the source of its `pallet::Call` instead
+        let handles = self
+            .pallets
+            .iter()
+            .cloned()
+            .map(|pallet| {
+                let root = root.to_path_buf();
+                let cache = cache.clone();
+                std::thread::spawn(move || pallet.run(&root, cache.as_ref(), mode))
+            })
+            .collect::<Vec<_>>();
+        let mut sources = Vec::with_capacity(handles.len());
+        for (module, handle) in modules.iter().zip(handles) {
+            let source = handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                .with_context(|| format!("Failed to extract pallet `{}`", module))?;
+            sources.push(source);
+        }
+
+        if let Some(output) = self.output.as_ref() {
+            let generate =
+                || -> anyhow::Result<String> { Ok(self.call_set(&sources)?.to_source()?) };
+            let content = match cache.as_ref() {
+                Some(cache) => cache.get_or_insert_with(self.cache_key(&sources)?, generate)?,
+                None => generate()?,
+            };
+            output::write(&root.join(output), &content, mode)?;
+        }
+        if let Some(dir) = self.output_dir.as_ref() {
+            let mut set = self.call_set(&sources)?;
+            if let Some(file_name) = self.file_name.clone() {
+                set = set.file_name(move |module| file_name.replace("{module}", module));
+            }
+            set.write_to_dir(root.join(dir), mode)?;
+        }
+        Ok(())
+    }
+
+    /// Parses the sources of all pallets into a set with the shared `types`
+    fn call_set(&self, sources: &[String]) -> anyhow::Result<CallSet> {
+        let mut set = CallSet::default();
+        if self.prelude {
+            set = set.prelude();
+        }
+        for (generic, ty) in &self.types {
+            set = set.resolve(generic, syn::parse_str(ty)?);
+        }
+        for (pallet, source) in self.pallets.iter().zip(sources) {
+            let call = PalletCallConfig::from_options(pallet.options.clone()).parse(source)?;
+            set = set.push(pallet.module()?, call);
+        }
+        Ok(set)
+    }
+
+    /// The cache key of the combined `output` of all pallets
+    fn cache_key(&self, sources: &[String]) -> anyhow::Result<[u8; 32]> {
+        let mut inputs = vec![serde_json::to_vec(&self.types)?, vec![self.prelude as u8]];
+        for (pallet, source) in self.pallets.iter().zip(sources) {
+            inputs.push(pallet.module()?.into_bytes());
+            inputs.push(pallet.cache_key(source)?.to_vec());
+        }
+        Ok(Cache::key(inputs.iter().map(Vec::as_slice)))
+    }
+}
+
+impl PalletManifest {
+    /// Returns the name of the module this pallet is emitted in
+    pub fn module(&self) -> anyhow::Result<String> {
+        if let Some(module) = self.module.as_ref() {
+            return Ok(module.clone());
+        }
+        if let Some(krate) = self.krate.as_ref() {
+            return Ok(krate.to_snake_case());
+        }
+        self.input
+            .as_ref()
+            .and_then(|input| input.file_stem())
+            .map(|stem| stem.to_string_lossy().to_snake_case())
+            .context("Pallet requires either a `crate` or an `input`")
+    }
+
+    /// Parses the pallet's `Call`, either from the `input` file or by
+    /// expanding the `crate`
+    pub fn parse(&self, root: &Path) -> anyhow::Result<PalletCall<'static>> {
+        let config = PalletCallConfig::from_options(self.options.clone());
+        Ok(config.parse(self.source(root)?)?)
+    }
+
+    /// Returns the source of the pallet's `Call`, either from the `input`
+    /// file or by expanding the `crate`
+    pub fn source(&self, root: &Path) -> anyhow::Result<String> {
+        if let Some(input) = self.input.as_ref() {
+            let input = root.join(input);
+            std::fs::read_to_string(&input)
+                .with_context(|| format!("Failed to read input {}", input.display()))
+        } else if let Some(krate) = self.krate.as_ref() {
+            Ok(Expander::default().expand_path(krate, "pallet::Call".parse()?)?)
+        } else {
+            anyhow::bail!("Pallet requires either a `crate` or an `input`")
+        }
+    }
+
+    /// Writes the pallet's `output`, if any, and returns the source of its
+    /// `Call`
+    ///
+    /// With a cache, the `Call` is only expanded if the source or the options
+    /// changed.
+    fn run(&self, root: &Path, cache: Option<&Cache>, mode: WriteMode) -> anyhow::Result<String> {
+        let source = self.source(root)?;
+        if let Some(output) = self.output.as_ref() {
+            let generate = || -> anyhow::Result<String> {
+                let call = PalletCallConfig::from_options(self.options.clone()).parse(&source)?;
+                if self.verify {
+                    verify(&call)?;
+                }
+                Ok(call.to_source()?)
+            };
+            let content = match cache {
+                Some(cache) => cache.get_or_insert_with(self.cache_key(&source)?, generate)?,
+                None => generate()?,
+            };
+            output::write(&root.join(output), &content, mode)?;
+        }
+        Ok(source)
+    }
+
+    /// The cache key of the pallet's `output`
+    fn cache_key(&self, source: &str) -> anyhow::Result<[u8; 32]> {
+        let options = serde_json::to_vec(&self.options)?;
+        Ok(Cache::key([source.as_bytes(), options.as_slice()]))
+    }
+}
+
+/// Checks that the generated code of the pallet compiles
+#[cfg(feature = "verify")]
+fn verify(call: &PalletCall) -> anyhow::Result<()> {
+    call.verify()
+}
+
+#[cfg(not(feature = "verify"))]
+fn verify(_: &PalletCall) -> anyhow::Result<()> {
+    anyhow::bail!("`verify` requires the `verify` feature")
+}
+
+/// Reads the manifest at the given path and extracts all pallets described in
+/// it
+///
+/// See [`Manifest`] for the format.
+pub fn run_manifest(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let manifest = Manifest::from_path(path)?;
+    let root = path.parent().unwrap_or_else(|| Path::new("."));
+    manifest.run(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_manifest() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            output = "calls.rs"
+
+            [types]
+            AccountId = "u64"
+
+            [[pallet]]
+            crate = "pallet-balances"
+            derives = ["Debug"]
+
+            [pallet.substitutions]
+            Balance = "u128"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.output, Some(PathBuf::from("calls.rs")));
+        assert_eq!(manifest.pallets.len(), 1);
+        assert_eq!(manifest.pallets[0].module().unwrap(), "pallet_balances");
+        assert_eq!(manifest.pallets[0].options.substitutions["Balance"], "u128");
+    }
+
+    #[test]
+    fn can_run_pallets_in_parallel() {
+        let root = crate::test_dir("manifest");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join("balances.rs"),
+            "pub enum Call<T: Config> { transfer(T::AccountId, T::Balance) }",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("system.rs"),
+            "pub enum Call<T: Config> { remark(Vec<u8>) }",
+        )
+        .unwrap();
+        let manifest: Manifest = toml::from_str(
+            r#"
+            output = "calls.rs"
+
+            [[pallet]]
+            input = "balances.rs"
+
+            [[pallet]]
+            input = "system.rs"
+        "#,
+        )
+        .unwrap();
+        manifest.run(&root).unwrap();
+
+        let calls = std::fs::read_to_string(root.join("calls.rs")).unwrap();
+        let balances = calls.find("mod balances").unwrap();
+        let system = calls.find("mod system").unwrap();
+        assert!(balances < system);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+//! Markdown reference of the calls of a `pallet::Call`
+
+use crate::CallInfo;
+
+/// Renders a markdown document with a table of all calls
+pub(crate) fn render(title: &str, calls: &[CallInfo]) -> String {
+    let mut out = format!("# {}\n\n", title);
+    out.push_str("| Index | Call | Parameters | Description |\n");
+    out.push_str("|------:|------|------------|-------------|\n");
+    for call in calls {
+        let args = call
+            .args
+            .iter()
+            .map(|arg| format!("`{}`", escape(&arg.to_string())))
+            .collect::<Vec<_>>();
+        out.push_str(&format!(
+            "| {} | `{}` | {} | {} |\n",
+            call.index,
+            call.name,
+            args.join(", "),
+            escape(&call.summary())
+        ));
+    }
+    out
+}
+
+/// Escapes characters that would break a table cell
+fn escape(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArgInfo;
+
+    #[test]
+    fn can_render_table() {
+        let calls = vec![CallInfo {
+            name: "transfer".to_string(),
+            index: 0,
+            docs: vec![
+                "Transfer some balance".to_string(),
+                "to another account".to_string(),
+                String::new(),
+                "# <weight>".to_string(),
+            ],
+            args: vec![ArgInfo {
+                name: Some("value".to_string()),
+                ty: "T::Balance".to_string(),
+                compact: true,
+            }],
+            origin: None,
+            weight: None,
+        }];
+        assert_eq!(
+            render("Balances", &calls),
+            "# Balances
+
+| Index | Call | Parameters | Description |
+|------:|------|------------|-------------|
+| 0 | `transfer` | `value: compact T::Balance` | Transfer some balance to another account |
+"
+        );
+    }
+}
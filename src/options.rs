@@ -0,0 +1,733 @@
+//! The serializable part of the `PalletCallConfig`
+
+use crate::{Error, Result};
+use anyhow::Context;
+use proc_macro2::Span;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path as FsPath, PathBuf};
+use syn::parse::Parser;
+use syn::{Attribute, Ident, ItemUse, Path, Type, WherePredicate};
+
+/// A header that traces the generated code back to its source
+///
+/// The placeholders `{tool}`, `{version}`, `{pallet}`, `{input_hash}` (the
+/// blake2-256 hash of the parsed `pallet::Call`) and `{options}` (all other
+/// options as JSON) are replaced when the header is rendered.
+pub const PROVENANCE_HEADER: &str = "\
+Generated by {tool} {version}, do not edit manually.
+
+pallet: {pallet}
+input: blake2-256 {input_hash}
+options: {options}";
+
+/// All options of the `PalletCallConfig` that can be stored in a config file
+///
+/// Derives, attributes, imports and types are stored as strings and parsed
+/// when expanding.
+///
+/// # Example
+///
+/// ```toml
+/// name = "BalancesCall"
+/// codec_crate = "codec"
+/// derives = ["Debug"]
+/// attributes = ["#[allow(clippy::large_enum_variant)]"]
+///
+/// [substitutions]
+/// Balance = "u128"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CallOptions {
+    /// Use this name for the Call enum, by default `Call` will be used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Prepend a comment rendered from this template to the generated
+    /// source, see [`PROVENANCE_HEADER`] for the placeholders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    /// Prepend this block verbatim to every written file, like a license
+    /// header or inner attributes such as `#![allow(clippy::all)]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preamble: Option<String>,
+    /// Format every written file with the system `rustfmt` instead of
+    /// `prettyplease` or the plain token output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rustfmt: Option<RustfmtOptions>,
+    /// How to expand call parameters to variant fields
+    pub parameter_style: ParameterStyle,
+    /// Whether to emit calls without parameters as unit variants instead of
+    /// empty tuple or struct variants
+    pub unit_variants: bool,
+    /// Whether to keep original comments
+    pub keep_comments: bool,
+    /// Whether to add the signature of the original dispatchable to the docs
+    pub signature_docs: bool,
+    /// The path to the scale codec crate by default it's `codec`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codec_crate: Option<String>,
+    /// The crate from which to derive `RuntimeDebug`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_debug: Option<String>,
+    /// Additional attributes, like `#[allow(dead_code)]`
+    pub attributes: Vec<String>,
+    /// Replaces the base derives `Clone, PartialEq, Eq, Encode, Decode`,
+    /// the `derives` are still added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_derives: Option<Vec<String>>,
+    /// Whether to omit the codec derives and all `#[codec(..)]` attributes,
+    /// for consumers that only need the shape of the enum
+    pub without_codec: bool,
+    /// Whether to derive `DecodeWithMemTracking` of the codec crate, which
+    /// newer runtimes require for calls
+    pub decode_with_mem_tracking: bool,
+    /// Whether to derive `MaxEncodedLen` of the codec crate, which requires
+    /// bounded field types
+    pub max_encoded_len: bool,
+    /// Emit `#[codec(encode_bound(..))]` with these predicates, like
+    /// `Balance: Encode`, instead of the inferred bounds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encode_bound: Option<Vec<String>>,
+    /// Emit `#[codec(decode_bound(..))]` with these predicates instead of the
+    /// inferred bounds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decode_bound: Option<Vec<String>>,
+    /// Whether to emit `#[codec(dumb_trait_bound)]`, which bounds every
+    /// generic instead of the field types
+    pub dumb_trait_bound: bool,
+    /// Additional derives, like `Debug`
+    pub derives: Vec<String>,
+    /// Derives that require `std`, these are only emitted with the `std`
+    /// feature
+    pub std_derives: Vec<String>,
+    /// Whether the generated code must only depend on `core` and `alloc`
+    pub no_std: bool,
+    /// Whether to emit the call indices as explicit discriminants
+    pub explicit_indices: bool,
+    /// Whether to emit conversions to and from `scale_value::Value`
+    pub scale_value: bool,
+    /// Whether to emit a static `<NAME>_CALLS` table that describes every call
+    pub calls_table: bool,
+    /// Whether to emit an `encoded_size_hint` method
+    pub size_hint: bool,
+    /// The un-expanded source of the pallet, like
+    /// `pallets/balances/src/lib.rs`, to classify the origin every call
+    /// requires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin_source: Option<PathBuf>,
+    /// Whether to keep the docs of the calls and document named fields with
+    /// the argument docs of their call, like ``- `dest`: The recipient``,
+    /// fields are only named with `ParameterStyle::Named`
+    pub call_docs: bool,
+    /// The un-expanded source of the pallet to take the docs of the calls
+    /// from, this implies `call_docs`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs_source: Option<PathBuf>,
+    /// Whether to emit a `<Name>Origin` enum and a `required_origin` method,
+    /// this requires the `origin_source`
+    pub required_origin: bool,
+    /// The benchmark generated `weights.rs` of the pallet, to attach the
+    /// weight of every call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weights_source: Option<PathBuf>,
+    /// Whether to emit a `<Name>Weight` struct and a `weight` method, this
+    /// requires the `weights_source`
+    pub static_weights: bool,
+    /// A `.json` or `.toml` file with hex encoded call data by call name, to
+    /// emit a test for every sample that decodes it with the concrete enum
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decode_fixtures: Option<PathBuf>,
+    /// Whether to emit examples of every call with the concrete enum and
+    /// their SCALE encoding
+    pub encoded_examples: bool,
+    /// The argument expressions of the examples by call name, calls without
+    /// arguments use `Default::default()` for every argument
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub example_args: BTreeMap<String, Vec<String>>,
+    /// Whether to emit a `for_each_<name>!` macro
+    pub for_each_call: bool,
+    /// Emit a `<Name>Filter` that matches the calls of this list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_filter: Option<CallFilterList>,
+    /// Whether to emit a `<Name>Types` trait with an associated type for
+    /// every generic and a `<Name>For<C>` alias that applies them
+    pub types_trait: bool,
+    /// Whether to emit a constructor function for every call that is generic
+    /// over the `<Name>Types` trait, this implies `types_trait`
+    pub trait_constructors: bool,
+    /// Whether to document every trait constructor with an example that
+    /// builds and encodes the call, this requires `trait_constructors`
+    pub constructor_examples: bool,
+    /// Emit a `<NAME>_HASHES` constant with the hashes of `<pallet>::<call>`,
+    /// using this pallet name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_hashes: Option<String>,
+    /// Whether to mark the generated enum `#[non_exhaustive]`
+    pub non_exhaustive: bool,
+    /// Whether to mark the generated enum `#[doc(hidden)]`
+    pub doc_hidden: bool,
+    /// Mark the variants of these calls `#[doc(hidden)]`
+    ///
+    /// Entries can be glob patterns like `force_*`
+    pub hidden_calls: Vec<String>,
+    /// Whether to emit `#[allow(missing_docs)]` on the generated enum
+    pub allow_missing_docs: bool,
+    /// The visibility of the generated items, like `pub(crate)`, by default
+    /// `pub`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    /// Wrap the generated code in a module with this name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    /// `use` statements to emit before the generated enum
+    pub imports: Vec<String>,
+    /// Only include these calls, all calls are included if empty
+    ///
+    /// Entries can be glob patterns like `transfer*`
+    pub include_calls: Vec<String>,
+    /// Exclude these calls
+    ///
+    /// Entries can be glob patterns like `force_*`
+    pub exclude_calls: Vec<String>,
+    /// How to handle `#[cfg(..)]` attributes
+    pub cfg: CfgPolicy,
+    /// Whether to turn warnings about dropped information into errors
+    pub deny_warnings: bool,
+    /// Whether to skip calls that can't be expanded instead of failing
+    pub lenient: bool,
+    /// Whether to validate the generated enum before it's emitted, and fail
+    /// with all findings
+    pub strict: bool,
+    /// How to emit fields marked with `#[codec(compact)]`
+    pub compact: CompactMode,
+    /// How to emit references to types of the `T: Config`, like
+    /// `&T::AccountId`
+    pub references: ReferenceMode,
+    /// Only keep attributes with these paths on the enum, variants and
+    /// fields, all attributes are kept if empty
+    pub allow_attributes: Vec<String>,
+    /// Remove attributes with these paths from the enum, variants and fields
+    pub deny_attributes: Vec<String>,
+    /// Whether to keep the `T: Config` generic instead of introducing
+    /// generics
+    pub keep_generics: bool,
+    /// Whether to emit constructors that take an `AccountId32` for calls
+    /// with lookup source parameters, like `<T::Lookup as StaticLookup>::Source`
+    pub lookup_helpers: bool,
+    /// Whether to keep the `__Ignore` variant that is skipped by the codec,
+    /// with a `PhantomData` of the generics as payload
+    pub keep_skipped_variants: bool,
+    /// Concrete types to use instead of generics, by generic name
+    pub substitutions: BTreeMap<String, String>,
+    /// Replacements of fully qualified type paths in all field types, like
+    /// `sp_runtime::MultiAddress` to `my_types::MultiAddress`
+    ///
+    /// The generic arguments of a replaced path are kept.
+    pub type_paths: BTreeMap<String, String>,
+    /// Use this type for fields with unsupported types instead of failing,
+    /// like `Vec<u8>`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opaque_type: Option<String>,
+    /// Concrete types for generics that are emitted as aliases in a `types`
+    /// module, by generic name
+    pub aliases: BTreeMap<String, String>,
+    /// Per call overrides by the original name of the call
+    pub overrides: BTreeMap<String, CallOverride>,
+    /// Also emit a concrete version of the generic enum
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concrete: Option<ConcreteCall>,
+}
+
+/// A concrete version of the generic enum, with `From` conversions between
+/// both
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConcreteCall {
+    /// The name of the concrete enum
+    pub name: String,
+    /// The concrete types of all generics, by generic name
+    pub types: BTreeMap<String, String>,
+}
+
+/// The allow and deny list of the emitted call filter, entries can be glob
+/// patterns like `force_*`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CallFilterList {
+    /// Only these calls match, all calls match if empty
+    pub allow: Vec<String>,
+    /// These calls never match
+    pub deny: Vec<String>,
+    /// The feature of the consuming crate that enables the
+    /// `frame_support::traits::Contains` impl, it's not emitted if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains_feature: Option<String>,
+}
+
+impl CallFilterList {
+    /// Whether the call with the given original name matches the filter
+    pub fn matches(&self, call: &str) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(|p| glob_match(p, call)))
+            && !self.deny.iter().any(|p| glob_match(p, call))
+    }
+}
+
+/// Overrides how a single call is expanded
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CallOverride {
+    /// Use this name for the variant instead of the converted call name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Additional attributes for the variant
+    pub attributes: Vec<String>,
+    /// How to expand the parameters of this call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter_style: Option<ParameterStyle>,
+    /// Concrete types to use instead of generics in this call, by generic
+    /// name
+    pub substitutions: BTreeMap<String, String>,
+}
+
+impl CallOverride {
+    pub(crate) fn parse_attributes(&self) -> Result<Vec<Attribute>> {
+        parse_attributes(&self.attributes)
+    }
+}
+
+impl ConcreteCall {
+    pub(crate) fn parse_types(&self) -> Result<BTreeMap<&str, Type>> {
+        parse_types(&self.types)
+    }
+}
+
+impl CallOptions {
+    /// Adds the derives and attributes of the profile
+    ///
+    /// Derives that are already configured are not added again, options that
+    /// are set afterwards override the profile.
+    pub fn apply_profile(&mut self, profile: Profile) {
+        let (derives, std_derives): (&[&str], &[&str]) = match profile {
+            Profile::Std => (&["Debug"], &["serde::Serialize", "serde::Deserialize"]),
+            Profile::NoStd => {
+                self.no_std = true;
+                // the codec crate is resolved at expansion time
+                self.max_encoded_len = true;
+                (&["scale_info::TypeInfo"], &[])
+            }
+            Profile::Indexer => (
+                &[
+                    "Debug",
+                    "serde::Serialize",
+                    "serde::Deserialize",
+                    "scale_info::TypeInfo",
+                ],
+                &[],
+            ),
+            Profile::Fuzz => {
+                let arbitrary =
+                    r#"#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]"#.to_string();
+                if !self.attributes.contains(&arbitrary) {
+                    self.attributes.push(arbitrary);
+                }
+                (&["Debug"], &[])
+            }
+        };
+        for derive in derives {
+            if !self.derives.iter().any(|d| d == derive) {
+                self.derives.push(derive.to_string());
+            }
+        }
+        for derive in std_derives {
+            if !self.std_derives.iter().any(|d| d == derive) {
+                self.std_derives.push(derive.to_string());
+            }
+        }
+    }
+
+    /// Deserializes the options from a toml string
+    pub fn from_toml_str(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    /// Reads the options from a `.json` or `.toml` file
+    pub fn from_path(path: impl AsRef<FsPath>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let options = if path
+            .extension()
+            .map(|ext| ext == "json")
+            .unwrap_or_default()
+        {
+            serde_json::from_str(&content)?
+        } else {
+            toml::from_str(&content)?
+        };
+        Ok(options)
+    }
+
+    /// Whether the call with the given original name should be part of the
+    /// generated `Call`
+    pub fn is_call_included(&self, call: &str) -> bool {
+        (self.include_calls.is_empty() || self.include_calls.iter().any(|p| glob_match(p, call)))
+            && !self.exclude_calls.iter().any(|p| glob_match(p, call))
+    }
+
+    /// Whether the variant of the call with the given original name is marked
+    /// `#[doc(hidden)]`
+    pub fn is_call_hidden(&self, call: &str) -> bool {
+        self.hidden_calls.iter().any(|p| glob_match(p, call))
+    }
+
+    /// Whether an attribute with the given path should be kept
+    ///
+    /// Doc attributes are only removed if they're explicitly denied.
+    pub fn is_attribute_allowed(&self, path: &Path) -> bool {
+        let path = quote::quote!(#path).to_string().replace(' ', "");
+        if self.deny_attributes.contains(&path) {
+            return false;
+        }
+        path == "doc" || self.allow_attributes.is_empty() || self.allow_attributes.contains(&path)
+    }
+
+    /// The path to the codec crate to use, by default `codec`
+    pub(crate) fn parse_codec_crate(&self) -> Result<Path> {
+        let codec_crate = self.codec_crate.as_deref().unwrap_or("codec");
+        parse_config("codec crate", codec_crate, syn::parse_str::<Path>)
+    }
+
+    pub(crate) fn parse_derives(&self) -> Result<Vec<Path>> {
+        self.derives
+            .iter()
+            .map(|d| parse_config("derive", d, syn::parse_str))
+            .collect()
+    }
+
+    /// Returns the configured base derives or `Clone, PartialEq, Eq, Encode,
+    /// Decode`, without `Encode` and `Decode` if `without_codec` is set
+    pub(crate) fn parse_base_derives(&self) -> Result<Vec<Path>> {
+        let base_derives = match self.base_derives.as_ref() {
+            Some(base_derives) => base_derives,
+            None if self.without_codec => {
+                return Ok(vec![
+                    syn::parse_quote!(Clone),
+                    syn::parse_quote!(PartialEq),
+                    syn::parse_quote!(Eq),
+                ]);
+            }
+            None => {
+                let codec_crate = self.parse_codec_crate()?;
+                return Ok(vec![
+                    syn::parse_quote!(Clone),
+                    syn::parse_quote!(PartialEq),
+                    syn::parse_quote!(Eq),
+                    syn::parse_quote!(#codec_crate::Encode),
+                    syn::parse_quote!(#codec_crate::Decode),
+                ]);
+            }
+        };
+        base_derives
+            .iter()
+            .map(|d| parse_config("derive", d, syn::parse_str))
+            .collect()
+    }
+
+    /// Returns the `#[codec(..)]` attributes that configure the bounds of the
+    /// codec derives
+    pub(crate) fn parse_codec_bounds(&self) -> Result<Vec<Attribute>> {
+        let mut attrs = Vec::new();
+        for (kind, bound) in [
+            ("encode_bound", &self.encode_bound),
+            ("decode_bound", &self.decode_bound),
+        ] {
+            if let Some(bound) = bound {
+                let predicates = bound
+                    .iter()
+                    .map(|p| parse_config(kind, p, syn::parse_str::<WherePredicate>))
+                    .collect::<Result<Vec<_>>>()?;
+                let kind = Ident::new(kind, Span::call_site());
+                attrs.push(syn::parse_quote!(#[codec(#kind( #( #predicates ),* ))]));
+            }
+        }
+        if self.dumb_trait_bound {
+            attrs.push(syn::parse_quote!(#[codec(dumb_trait_bound)]));
+        }
+        Ok(attrs)
+    }
+
+    pub(crate) fn parse_std_derives(&self) -> Result<Vec<Path>> {
+        self.std_derives
+            .iter()
+            .map(|d| parse_config("derive", d, syn::parse_str))
+            .collect()
+    }
+
+    pub(crate) fn parse_attributes(&self) -> Result<Vec<Attribute>> {
+        parse_attributes(&self.attributes)
+    }
+
+    pub(crate) fn parse_imports(&self) -> Result<Vec<ItemUse>> {
+        self.imports
+            .iter()
+            .map(|i| parse_config("import", i, syn::parse_str))
+            .collect()
+    }
+
+    pub(crate) fn parse_substitutions(&self) -> Result<BTreeMap<&str, Type>> {
+        parse_types(&self.substitutions)
+    }
+
+    /// Returns the type that replaces unsupported field types, if configured
+    pub(crate) fn parse_opaque_type(&self) -> Result<Option<Type>> {
+        self.opaque_type
+            .as_deref()
+            .map(|ty| parse_config("opaque type", ty, syn::parse_str))
+            .transpose()
+    }
+
+    /// Returns the `type_paths` replacements, keyed by the path without its
+    /// leading `::`, like `sp_runtime::MultiAddress`
+    pub(crate) fn parse_type_paths(&self) -> Result<BTreeMap<String, Path>> {
+        self.type_paths
+            .iter()
+            .map(|(path, replacement)| {
+                let path = parse_config("type path", path, syn::parse_str::<Path>)?;
+                let replacement = parse_config("type path", replacement, syn::parse_str)?;
+                Ok((path_key(&path), replacement))
+            })
+            .collect()
+    }
+}
+
+/// The segments of the path without generic arguments, like
+/// `sp_runtime::MultiAddress`
+pub(crate) fn path_key(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Parses a configured value, failing with an `Error::Config`
+pub(crate) fn parse_config<T>(
+    kind: &str,
+    value: &str,
+    parse: impl FnOnce(&str) -> syn::Result<T>,
+) -> Result<T> {
+    parse(value).map_err(|err| Error::Config(format!("{} `{}`: {}", kind, value, err)))
+}
+
+pub(crate) fn parse_attributes(attributes: &[String]) -> Result<Vec<Attribute>> {
+    let mut attrs = Vec::with_capacity(attributes.len());
+    for attr in attributes {
+        attrs.extend(parse_config("attribute", attr, |s| {
+            Attribute::parse_outer.parse_str(s)
+        })?);
+    }
+    Ok(attrs)
+}
+
+pub(crate) fn parse_types(types: &BTreeMap<String, String>) -> Result<BTreeMap<&str, Type>> {
+    types
+        .iter()
+        .map(|(name, ty)| {
+            parse_config("substitution", ty, syn::parse_str).map(|ty| (name.as_str(), ty))
+        })
+        .collect()
+}
+
+/// Matches the name against a glob pattern, where `*` matches any sequence of
+/// characters and `?` matches a single character
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    // position of the last `*` in the pattern and the matching position in name
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// How to handle `#[cfg(..)]` and `#[cfg_attr(..)]` attributes of the
+/// variants and fields of the original `Call`
+///
+/// In a config file this is either `cfg = "keep"`, `cfg = "strip"` or a map
+/// of feature names:
+///
+/// ```toml
+/// [cfg.map]
+/// runtime-benchmarks = "benchmarks"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CfgPolicy {
+    /// Keep all attributes as they are
+    #[default]
+    Keep,
+    /// Remove all attributes, so that feature gated calls are always present
+    Strip,
+    /// Keep all attributes but rename the features in `feature = ".."`
+    /// predicates, features that are not in the map are kept as they are
+    Map(BTreeMap<String, String>),
+}
+
+/// How the system `rustfmt` formats the generated code
+///
+/// # Example
+///
+/// ```toml
+/// [rustfmt]
+/// edition = "2021"
+/// config_path = "rustfmt.toml"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RustfmtOptions {
+    /// The edition to format with, like `2021`, by default the one of
+    /// `rustfmt`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edition: Option<String>,
+    /// The `rustfmt.toml` to use, or the directory to search it in, by
+    /// default `rustfmt` only uses its built-in defaults
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_path: Option<PathBuf>,
+}
+
+/// A named bundle of derives and attributes for a common use of the
+/// generated enum, see `CallOptions::apply_profile`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Profile {
+    /// `Debug` and `serde::Serialize`/`serde::Deserialize` with the `std`
+    /// feature
+    Std,
+    /// `no_std` code with `scale_info::TypeInfo` and `MaxEncodedLen`, which
+    /// requires bounded field types
+    NoStd,
+    /// `Debug`, `serde::Serialize`/`serde::Deserialize` and
+    /// `scale_info::TypeInfo` for services that decode and store calls
+    Indexer,
+    /// `Debug` and `arbitrary::Arbitrary` with the `fuzz` feature to generate
+    /// calls in fuzz tests
+    Fuzz,
+}
+
+/// How to emit fields that are marked with `#[codec(compact)]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactMode {
+    /// Keep the `#[codec(compact)]` attribute
+    #[default]
+    Keep,
+    /// Remove the attribute, the field is then encoded as a regular value
+    Strip,
+    /// Remove the attribute and wrap the type in `codec::Compact<T>`, which
+    /// encodes identical to the attribute
+    Wrap,
+}
+
+/// How to emit fields that are references to types of the `T: Config`, like
+/// `&T::AccountId` or `&[T::Hash]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceMode {
+    /// Keep the reference, with the lifetime `'a` if it has none
+    #[default]
+    Borrowed,
+    /// Use the owned type instead, `&[T]` becomes `Vec<T>`
+    Owned,
+}
+
+/// How to expand the call parameters as enum variant fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterStyle {
+    /// Use default `(ty,ty)` unnamed fields
+    #[default]
+    Unnamed,
+    /// Expand call parameters as named fields
+    // TODO add convert type for determine the name, allow extracting it from the ast of the actual
+    // function fn(call_name, index)
+    Named,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_load_options() {
+        let options = CallOptions::from_toml_str(
+            r##"
+            name = "BalancesCall"
+            derives = ["Debug"]
+            attributes = ["#[allow(dead_code)]"]
+
+            [substitutions]
+            Balance = "u128"
+        "##,
+        )
+        .unwrap();
+
+        assert_eq!(options.name.as_deref(), Some("BalancesCall"));
+        assert_eq!(options.parameter_style, ParameterStyle::Unnamed);
+        assert_eq!(options.parse_derives().unwrap().len(), 1);
+        assert_eq!(options.parse_attributes().unwrap().len(), 1);
+
+        let serialized = toml::to_string(&options).unwrap();
+        assert_eq!(CallOptions::from_toml_str(&serialized).unwrap(), options);
+    }
+
+    #[test]
+    fn can_filter_attributes() {
+        let options = CallOptions {
+            allow_attributes: vec!["codec".to_string(), "rustfmt::skip".to_string()],
+            deny_attributes: vec!["rustfmt::skip".to_string()],
+            ..Default::default()
+        };
+        assert!(options.is_attribute_allowed(&syn::parse_quote!(codec)));
+        assert!(options.is_attribute_allowed(&syn::parse_quote!(doc)));
+        assert!(!options.is_attribute_allowed(&syn::parse_quote!(rustfmt::skip)));
+        assert!(!options.is_attribute_allowed(&syn::parse_quote!(cfg_attr)));
+    }
+
+    #[test]
+    fn can_match_globs() {
+        assert!(glob_match("force_*", "force_transfer"));
+        assert!(glob_match("*_balance", "set_balance"));
+        assert!(glob_match("transfer?", "transfer2"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("force_*", "transfer"));
+        assert!(!glob_match("transfer?", "transfer"));
+
+        let options = CallOptions {
+            exclude_calls: vec!["force_*".to_string(), "set_*".to_string()],
+            ..Default::default()
+        };
+        assert!(options.is_call_included("transfer"));
+        assert!(!options.is_call_included("force_transfer"));
+        assert!(!options.is_call_included("set_balance"));
+    }
+}
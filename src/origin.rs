@@ -0,0 +1,194 @@
+//! Classifies the origin each dispatchable requires, from the un-expanded
+//! source of the pallet
+
+use crate::{Error, Result};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use syn::ext::IdentExt;
+use syn::{ImplItem, Item};
+
+/// The origin a dispatchable requires
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OriginKind {
+    /// `ensure_root`
+    Root,
+    /// `ensure_signed`
+    Signed,
+    /// `ensure_none`, for unsigned transactions and inherents
+    None,
+    /// `ensure_origin` of a configured origin, like `T::AdminOrigin`
+    Custom(String),
+}
+
+impl fmt::Display for OriginKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OriginKind::Root => write!(f, "root"),
+            OriginKind::Signed => write!(f, "signed"),
+            OriginKind::None => write!(f, "none"),
+            OriginKind::Custom(origin) => write!(f, "{}", origin),
+        }
+    }
+}
+
+/// Classifies the origin of every dispatchable of the `#[pallet::call]` impl
+/// in the un-expanded source of a pallet, by the name of the dispatchable
+///
+/// The first origin check in the body of a dispatchable determines its
+/// origin: `ensure_root`, `ensure_signed`, `ensure_none` or the
+/// `ensure_origin` of a configured origin like `T::AdminOrigin`.
+/// Dispatchables without any of these checks are not included.
+pub fn call_origins(source: &str) -> Result<BTreeMap<String, OriginKind>> {
+    let file = syn::parse_file(source)?;
+    let mut origins = BTreeMap::new();
+    collect_origins(&file.items, &mut origins);
+    Ok(origins)
+}
+
+/// Reads the pallet source from the file and classifies its dispatchables,
+/// see [`call_origins`]
+pub(crate) fn call_origins_from_path(path: &Path) -> Result<BTreeMap<String, OriginKind>> {
+    let source = std::fs::read_to_string(path).map_err(|err| {
+        Error::Config(format!(
+            "failed to read pallet source {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    call_origins(&source)
+}
+
+fn collect_origins(items: &[Item], origins: &mut BTreeMap<String, OriginKind>) {
+    for item in items {
+        match item {
+            Item::Mod(module) => {
+                if let Some((_, items)) = module.content.as_ref() {
+                    collect_origins(items, origins);
+                }
+            }
+            Item::Impl(item) if item.attrs.iter().any(is_pallet_call) => {
+                for item in &item.items {
+                    if let ImplItem::Method(method) = item {
+                        if let Some(origin) = classify(method.block.to_token_stream()) {
+                            origins.insert(method.sig.ident.unraw().to_string(), origin);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether the attribute is `#[pallet::call]`
+pub(crate) fn is_pallet_call(attr: &syn::Attribute) -> bool {
+    let segments = attr
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    segments == ["pallet", "call"]
+}
+
+/// Returns the origin of the first origin check in the tokens
+fn classify(tokens: TokenStream) -> Option<OriginKind> {
+    let mut flat = Vec::new();
+    flatten(tokens, &mut flat);
+    for (idx, token) in flat.iter().enumerate() {
+        let ident = match token {
+            TokenTree::Ident(ident) => ident.to_string(),
+            _ => continue,
+        };
+        match ident.as_str() {
+            "ensure_root" => return Some(OriginKind::Root),
+            "ensure_signed" => return Some(OriginKind::Signed),
+            "ensure_none" => return Some(OriginKind::None),
+            "ensure_origin" => return Some(OriginKind::Custom(origin_path(&flat[..idx]))),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The path in front of `ensure_origin`, like `T::AdminOrigin`
+fn origin_path(tokens: &[TokenTree]) -> String {
+    let mut segments = Vec::new();
+    for token in tokens.iter().rev() {
+        match token {
+            TokenTree::Ident(ident) => segments.push(ident.to_string()),
+            TokenTree::Punct(punct) if punct.as_char() == ':' => {}
+            _ => break,
+        }
+    }
+    if segments.is_empty() {
+        return "ensure_origin".to_string();
+    }
+    segments.reverse();
+    segments.join("::")
+}
+
+/// Flattens the tokens of all groups, in order
+fn flatten(tokens: TokenStream, flat: &mut Vec<TokenTree>) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => flatten(group.stream(), flat),
+            token => flat.push(token),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_classify_origins() {
+        let origins = call_origins(
+            r#"
+            #[frame_support::pallet]
+            pub mod pallet {
+                #[pallet::call]
+                impl<T: Config> Pallet<T> {
+                    #[pallet::weight(0)]
+                    pub fn transfer(origin: OriginFor<T>, dest: T::AccountId) -> DispatchResult {
+                        let who = ensure_signed(origin)?;
+                        Ok(())
+                    }
+
+                    #[pallet::weight(0)]
+                    pub fn set_balance(origin: OriginFor<T>) -> DispatchResult {
+                        ensure_root(origin)?;
+                        Ok(())
+                    }
+
+                    #[pallet::weight(0)]
+                    pub fn force_unreserve(origin: OriginFor<T>) -> DispatchResult {
+                        T::AdminOrigin::ensure_origin(origin)?;
+                        Ok(())
+                    }
+
+                    #[pallet::weight(0)]
+                    pub fn noop(_origin: OriginFor<T>) -> DispatchResult {
+                        Ok(())
+                    }
+                }
+            }
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(origins.len(), 3);
+        assert_eq!(origins["transfer"], OriginKind::Signed);
+        assert_eq!(origins["set_balance"], OriginKind::Root);
+        assert_eq!(
+            origins["force_unreserve"],
+            OriginKind::Custom("T::AdminOrigin".to_string())
+        );
+    }
+}
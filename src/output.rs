@@ -0,0 +1,156 @@
+//! Rendering and writing of the generated code
+
+use crate::{options, CallOptions, Error, Result, RustfmtOptions};
+use proc_macro2::TokenStream;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// How to handle an already existing output file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// Always write the output, replacing any existing file
+    Overwrite,
+    /// Only write the output if it differs from the content of the existing
+    /// file, this keeps the modification time of unchanged files untouched
+    #[default]
+    IfChanged,
+    /// Never write the output and fail if it differs from the content of the
+    /// existing file, so CI can verify that committed generated code is up to
+    /// date
+    Check,
+}
+
+/// Formats the tokens with `prettyplease`
+#[cfg(feature = "pretty")]
+pub(crate) fn pretty(tokens: TokenStream) -> syn::Result<String> {
+    let file = syn::parse2::<syn::File>(tokens)?;
+    Ok(prettyplease::unparse(&file))
+}
+
+/// Renders the tokens as source code, pretty formatted if the `pretty` feature
+/// is enabled
+pub(crate) fn render(tokens: TokenStream) -> syn::Result<String> {
+    #[cfg(feature = "pretty")]
+    {
+        pretty(tokens)
+    }
+    #[cfg(not(feature = "pretty"))]
+    {
+        Ok(tokens.to_string())
+    }
+}
+
+/// Prepends the `preamble` of the options to the rendered source and formats
+/// it with `rustfmt`, if configured
+pub(crate) fn finish(source: String, options: Option<&CallOptions>) -> Result<String> {
+    let source = match options.and_then(|options| options.preamble.as_deref()) {
+        Some(preamble) => {
+            // the preamble must be valid at the start of a file
+            options::parse_config("preamble", preamble, syn::parse_file)?;
+            format!("{}\n{}", preamble.trim_end(), source)
+        }
+        None => source,
+    };
+    match options.and_then(|options| options.rustfmt.as_ref()) {
+        Some(rustfmt) => format_with_rustfmt(&source, rustfmt),
+        None => Ok(source),
+    }
+}
+
+/// Pipes the source through the system `rustfmt`, `RUSTFMT` overrides the
+/// binary like it does for cargo
+pub(crate) fn format_with_rustfmt(source: &str, options: &RustfmtOptions) -> Result<String> {
+    let rustfmt = std::env::var_os("RUSTFMT").unwrap_or_else(|| "rustfmt".into());
+    let mut cmd = Command::new(rustfmt);
+    cmd.arg("--emit=stdout");
+    if let Some(edition) = options.edition.as_ref() {
+        cmd.arg("--edition").arg(edition);
+    }
+    if let Some(config_path) = options.config_path.as_ref() {
+        cmd.arg("--config-path").arg(config_path);
+    }
+    let failed = |err: std::io::Error| Error::Config(format!("failed to run rustfmt: {}", err));
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(failed)?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(source.as_bytes())
+        .map_err(failed)?;
+    let output = child.wait_with_output().map_err(failed)?;
+    if !output.status.success() {
+        return Err(Error::Config(format!(
+            "rustfmt failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Writes the content to the given path according to the `WriteMode`
+///
+/// Returns `true` if the file was written
+pub(crate) fn write(path: &Path, content: &str, mode: WriteMode) -> std::io::Result<bool> {
+    if mode == WriteMode::Check {
+        return match std::fs::read_to_string(path) {
+            Ok(existing) if existing == content => Ok(false),
+            _ => Err(std::io::Error::other(format!(
+                "{} is not up to date",
+                path.display()
+            ))),
+        };
+    }
+    if mode == WriteMode::IfChanged {
+        if let Ok(existing) = std::fs::read_to_string(path) {
+            if existing == content {
+                return Ok(false);
+            }
+        }
+    }
+    std::fs::write(path, content)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_only_if_changed() {
+        let dir = crate::test_dir("write");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("call.rs");
+
+        assert!(write(&path, "pub enum Call {}", WriteMode::IfChanged).unwrap());
+        assert!(!write(&path, "pub enum Call {}", WriteMode::IfChanged).unwrap());
+        assert!(write(&path, "pub enum Call {}", WriteMode::Overwrite).unwrap());
+        assert!(write(&path, "pub enum Call<A> {}", WriteMode::IfChanged).unwrap());
+        assert!(!write(&path, "pub enum Call<A> {}", WriteMode::Check).unwrap());
+        assert!(write(&path, "pub enum Call {}", WriteMode::Check).is_err());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "pub enum Call<A> {}"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn can_format_with_rustfmt() {
+        let options = CallOptions {
+            rustfmt: Some(RustfmtOptions {
+                edition: Some("2018".to_string()),
+                config_path: None,
+            }),
+            ..Default::default()
+        };
+        let source = finish("pub enum Call<A> { Remark(A) }".to_string(), Some(&options)).unwrap();
+        assert_eq!(source, "pub enum Call<A> {\n    Remark(A),\n}\n");
+    }
+}
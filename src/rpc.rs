@@ -0,0 +1,286 @@
+//! Verifies generated enums against the metadata of a live chain
+
+use crate::diff::diff_calls;
+use crate::{ArgInfo, CallDiff, CallInfo, PalletCall, PalletIndex};
+use anyhow::Context;
+use codec::{Decode, Encode};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use scale_info::{PortableRegistry, TypeDef};
+
+/// Fetches the metadata of the node at `url` with `state_getMetadata`
+///
+/// Nodes return V14 metadata this way, use `fetch_metadata_at_version` for
+/// newer versions.
+pub fn fetch_metadata(url: &str) -> anyhow::Result<RuntimeMetadataPrefixed> {
+    let bytes = request(url, "state_getMetadata", serde_json::json!([]))?;
+    Ok(RuntimeMetadataPrefixed::decode(&mut bytes.as_slice())?)
+}
+
+/// Fetches the metadata of the node at `url` in the given version, like
+/// `15`, with the `Metadata_metadata_at_version` runtime API
+///
+/// Returns `None` if the runtime doesn't support the version.
+pub fn fetch_metadata_at_version(
+    url: &str,
+    version: u32,
+) -> anyhow::Result<Option<RuntimeMetadataPrefixed>> {
+    let params = serde_json::json!([
+        "Metadata_metadata_at_version",
+        format!("0x{}", hex::encode(version.encode())),
+    ]);
+    let bytes = request(url, "state_call", params)?;
+    // the runtime API returns an `Option<OpaqueMetadata>`
+    match Option::<Vec<u8>>::decode(&mut bytes.as_slice())? {
+        Some(metadata) => Ok(Some(RuntimeMetadataPrefixed::decode(
+            &mut metadata.as_slice(),
+        )?)),
+        None => Ok(None),
+    }
+}
+
+/// Sends a JSON-RPC request and decodes the hex encoded result
+fn request(url: &str, method: &str, params: serde_json::Value) -> anyhow::Result<Vec<u8>> {
+    let response: serde_json::Value = ureq::post(url)
+        .send_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))
+        .with_context(|| format!("Failed to call `{}` on {}", method, url))?
+        .into_json()?;
+    if let Some(err) = response.get("error") {
+        anyhow::bail!("`{}` failed: {}", method, err)
+    }
+    let result = response["result"]
+        .as_str()
+        .with_context(|| format!("Invalid `{}` response", method))?;
+    Ok(hex::decode(result.trim_start_matches("0x"))?)
+}
+
+/// A runtime API of the chain, only part of V15 metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeApiInfo {
+    /// The name of the API, like `Core`
+    pub name: String,
+    /// The names of the methods of the API, like `version`
+    pub methods: Vec<String>,
+    /// The lines of the API's doc comment
+    pub docs: Vec<String>,
+}
+
+/// A pallet of the metadata, regardless of the version
+struct PalletInfo<'a> {
+    name: &'a str,
+    index: u8,
+    /// The type id of the pallet's `Call`
+    calls: Option<u32>,
+}
+
+/// Returns the type registry and all pallets of the metadata
+fn pallets(
+    metadata: &RuntimeMetadataPrefixed,
+) -> anyhow::Result<(&PortableRegistry, Vec<PalletInfo<'_>>)> {
+    match &metadata.1 {
+        RuntimeMetadata::V14(metadata) => Ok((
+            &metadata.types,
+            metadata
+                .pallets
+                .iter()
+                .map(|pallet| PalletInfo {
+                    name: &pallet.name,
+                    index: pallet.index,
+                    calls: pallet.calls.as_ref().map(|calls| calls.ty.id),
+                })
+                .collect(),
+        )),
+        RuntimeMetadata::V15(metadata) => Ok((
+            &metadata.types,
+            metadata
+                .pallets
+                .iter()
+                .map(|pallet| PalletInfo {
+                    name: &pallet.name,
+                    index: pallet.index,
+                    calls: pallet.calls.as_ref().map(|calls| calls.ty.id),
+                })
+                .collect(),
+        )),
+        metadata => anyhow::bail!("Unsupported metadata version {}", metadata.version()),
+    }
+}
+
+/// Returns the variants of the outer `RuntimeCall` enum, one per pallet with
+/// the pallet's index
+///
+/// V15 metadata describes the outer enum directly, for V14 it's the `Call`
+/// type parameter of the extrinsic.
+pub fn runtime_calls(metadata: &RuntimeMetadataPrefixed) -> anyhow::Result<Vec<CallInfo>> {
+    match &metadata.1 {
+        RuntimeMetadata::V14(metadata) => {
+            let extrinsic = metadata
+                .types
+                .resolve(metadata.extrinsic.ty.id)
+                .context("Unknown extrinsic type")?;
+            let call = extrinsic
+                .type_params
+                .iter()
+                .find(|param| param.name == "Call")
+                .and_then(|param| param.ty.as_ref())
+                .context("The extrinsic type has no `Call` parameter")?;
+            registry_calls(&metadata.types, call.id)
+        }
+        RuntimeMetadata::V15(metadata) => {
+            registry_calls(&metadata.types, metadata.outer_enums.call_enum_ty.id)
+        }
+        metadata => anyhow::bail!("Unsupported metadata version {}", metadata.version()),
+    }
+}
+
+/// Returns all pallets of the metadata with their index, in declaration
+/// order
+pub fn metadata_pallets(metadata: &RuntimeMetadataPrefixed) -> anyhow::Result<Vec<PalletIndex>> {
+    let (_, pallets) = pallets(metadata)?;
+    Ok(pallets
+        .into_iter()
+        .map(|pallet| PalletIndex {
+            name: pallet.name.to_string(),
+            index: pallet.index,
+        })
+        .collect())
+}
+
+/// Returns all runtime APIs of V15 metadata, older versions have none
+pub fn runtime_apis(metadata: &RuntimeMetadataPrefixed) -> Vec<RuntimeApiInfo> {
+    match &metadata.1 {
+        RuntimeMetadata::V15(metadata) => metadata
+            .apis
+            .iter()
+            .map(|api| RuntimeApiInfo {
+                name: api.name.clone(),
+                methods: api.methods.iter().map(|m| m.name.clone()).collect(),
+                docs: api.docs.clone(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the calls of the pallet with the given name, like `Balances`, as
+/// they're described in the metadata
+///
+/// The type of a parameter is the name of the type in the pallet, like
+/// `T::Balance`.
+pub fn metadata_calls(
+    metadata: &RuntimeMetadataPrefixed,
+    pallet: &str,
+) -> anyhow::Result<Vec<CallInfo>> {
+    let (types, pallets) = pallets(metadata)?;
+    let calls = pallets
+        .iter()
+        .find(|p| p.name == pallet)
+        .with_context(|| format!("No pallet `{}` in the metadata", pallet))?
+        .calls
+        .with_context(|| format!("Pallet `{}` has no calls", pallet))?;
+    registry_calls(types, calls)
+}
+
+/// Compares the calls of the generated enum with the calls of the pallet in
+/// the metadata of a chain
+///
+/// The metadata is the old and the generated enum the new version of the
+/// diff, calls that are excluded from the enum are reported as removed. Only
+/// the number and compactness of the parameters are compared, the metadata
+/// has no original types.
+pub fn verify_metadata(
+    call: &PalletCall,
+    metadata: &RuntimeMetadataPrefixed,
+    pallet: &str,
+) -> anyhow::Result<CallDiff> {
+    let expected = metadata_calls(metadata, pallet)?;
+    let generated = call.metadata()?.calls;
+    Ok(diff_calls(&expected, &generated, same_shape))
+}
+
+/// Whether the parameters would be encoded the same way
+fn same_shape(old: &[ArgInfo], new: &[ArgInfo]) -> bool {
+    old.len() == new.len()
+        && old
+            .iter()
+            .zip(new)
+            .all(|(old, new)| old.compact == new.compact)
+}
+
+/// Returns the variants of the call enum with the given type id
+fn registry_calls(types: &PortableRegistry, id: u32) -> anyhow::Result<Vec<CallInfo>> {
+    let variants = match types.resolve(id).map(|ty| &ty.type_def) {
+        Some(TypeDef::Variant(def)) => &def.variants,
+        Some(_) => anyhow::bail!("The call type {} is not an enum", id),
+        None => anyhow::bail!("Unknown call type {}", id),
+    };
+    Ok(variants
+        .iter()
+        .map(|variant| CallInfo {
+            name: variant.name.clone(),
+            index: variant.index,
+            docs: variant.docs.clone(),
+            args: variant
+                .fields
+                .iter()
+                .map(|field| ArgInfo {
+                    name: field.name.clone(),
+                    ty: field
+                        .type_name
+                        .clone()
+                        .unwrap_or_else(|| format!("#{}", field.ty.id)),
+                    compact: matches!(
+                        types.resolve(field.ty.id).map(|ty| &ty.type_def),
+                        Some(TypeDef::Compact(_))
+                    ),
+                })
+                .collect(),
+            origin: None,
+            weight: None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PalletCallConfig;
+    use scale_info::{meta_type, Registry};
+
+    #[allow(non_camel_case_types, dead_code)]
+    #[derive(codec::Encode, scale_info::TypeInfo)]
+    enum Call {
+        transfer(u64, #[codec(compact)] u128),
+        #[codec(index = 3)]
+        remark(Vec<u8>),
+    }
+
+    #[test]
+    fn can_verify_calls() {
+        let mut registry = Registry::new();
+        let id = registry.register_type(&meta_type::<Call>()).id;
+        let types: PortableRegistry = registry.into();
+        let expected = registry_calls(&types, id).unwrap();
+
+        let call = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+                remark(Vec<u8>),
+            }
+        "#,
+            )
+            .unwrap();
+        let generated = call.metadata().unwrap().calls;
+        let diff = diff_calls(&expected, &generated, same_shape);
+        assert_eq!(
+            diff.to_string(),
+            "- changed index of `remark` from 3 to 1\n"
+        );
+    }
+}
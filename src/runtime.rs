@@ -0,0 +1,255 @@
+//! Extracts the pallet indices of a runtime from its `construct_runtime!`
+
+use crate::{output, parse_ident, Error, Result};
+use proc_macro2::{Delimiter, Literal, TokenStream, TokenTree};
+use quote::quote;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use syn::{Item, Lit, Meta, NestedMeta};
+
+/// A pallet of a runtime with the index its calls are encoded with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PalletIndex {
+    /// The name of the pallet in the runtime, like `Balances`
+    pub name: String,
+    /// The index of the pallet
+    pub index: u8,
+}
+
+/// Returns all pallets of the runtime with their index, in declaration
+/// order
+///
+/// The pallets are parsed from the `construct_runtime!` invocation in the
+/// source of the runtime, or from the `#[runtime::pallet_index(..)]` type
+/// aliases of a `#[frame_support::runtime]` module. Pallets without an
+/// explicit index get the index of the previous pallet plus one, like
+/// `construct_runtime!` does.
+pub fn construct_runtime_pallets(source: &str) -> Result<Vec<PalletIndex>> {
+    let file = syn::parse_file(source)?;
+    let mut pallets = Vec::new();
+    collect_pallets(&file.items, &mut pallets)?;
+    if pallets.is_empty() {
+        return Err(Error::Config(
+            "no `construct_runtime!` found in the runtime source".to_string(),
+        ));
+    }
+    let mut indices = BTreeMap::new();
+    for pallet in &pallets {
+        if let Some(other) = indices.insert(pallet.index, &pallet.name) {
+            return Err(Error::Config(format!(
+                "the pallets `{}` and `{}` have the same index {}",
+                other, pallet.name, pallet.index
+            )));
+        }
+    }
+    Ok(pallets)
+}
+
+fn collect_pallets(items: &[Item], pallets: &mut Vec<PalletIndex>) -> Result<()> {
+    for item in items {
+        match item {
+            Item::Macro(item) if item.mac.path.is_ident("construct_runtime") => {
+                pallets.extend(construct_runtime(item.mac.tokens.clone())?);
+            }
+            Item::Mod(module) => {
+                if let Some((_, items)) = module.content.as_ref() {
+                    collect_pallets(items, pallets)?;
+                }
+            }
+            Item::Type(item) => {
+                if let Some(index) = item.attrs.iter().find_map(pallet_index) {
+                    pallets.push(PalletIndex {
+                        name: item.ident.to_string(),
+                        index,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// The index of `#[runtime::pallet_index(N)]`
+fn pallet_index(attr: &syn::Attribute) -> Option<u8> {
+    let segments = attr
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    if segments != ["runtime", "pallet_index"] {
+        return None;
+    }
+    match attr.parse_meta().ok()? {
+        Meta::List(list) => match list.nested.first()? {
+            NestedMeta::Lit(Lit::Int(index)) => index.base10_parse().ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parses the pallets of `construct_runtime!`, which are the entries of the
+/// last braced group, like `System: frame_system = 0,`
+fn construct_runtime(tokens: TokenStream) -> Result<Vec<PalletIndex>> {
+    let body = tokens
+        .into_iter()
+        .filter_map(|token| match token {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => Some(group),
+            _ => None,
+        })
+        .last()
+        .ok_or_else(|| Error::Config("`construct_runtime!` has no pallets".to_string()))?;
+
+    let mut entries = vec![Vec::new()];
+    for token in body.stream() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => entries.push(Vec::new()),
+            token => entries.last_mut().expect("not empty").push(token),
+        }
+    }
+
+    let mut pallets = Vec::new();
+    let mut next = 0u16;
+    for entry in entries.into_iter().filter(|entry| !entry.is_empty()) {
+        // skip attributes like `#[cfg(..)]`
+        let mut tokens = entry.iter();
+        let mut name = None;
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                    tokens.next();
+                }
+                TokenTree::Ident(ident) => {
+                    name = Some(ident.to_string());
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let name = name.ok_or_else(|| {
+            Error::Config("expected the name of a pallet in `construct_runtime!`".to_string())
+        })?;
+        let explicit = entry.windows(2).find_map(|pair| match pair {
+            [TokenTree::Punct(punct), TokenTree::Literal(lit)] if punct.as_char() == '=' => {
+                Some(lit.to_string())
+            }
+            _ => None,
+        });
+        let index = match explicit {
+            Some(lit) => lit.parse::<u16>().map_err(|_| {
+                Error::Config(format!("invalid index `{}` of pallet `{}`", lit, name))
+            })?,
+            None => next,
+        };
+        if index > u8::MAX as u16 {
+            return Err(Error::Config(format!(
+                "the index {} of pallet `{}` exceeds the maximum of 255",
+                index, name
+            )));
+        }
+        next = index + 1;
+        pallets.push(PalletIndex {
+            name,
+            index: index as u8,
+        });
+    }
+    Ok(pallets)
+}
+
+/// Renders the enum of `emit_pallet_enum` as source code
+pub fn pallet_enum_source(name: &str, pallets: &[PalletIndex]) -> Result<String> {
+    Ok(output::render(emit_pallet_enum(name, pallets)?)?)
+}
+
+/// Emits an enum of all pallets with their index as discriminant, with
+/// `name`, `index` and `from_index` helpers
+pub fn emit_pallet_enum(name: &str, pallets: &[PalletIndex]) -> Result<TokenStream> {
+    let name = parse_ident(name)?;
+    let variants = pallets
+        .iter()
+        .map(|pallet| parse_ident(&pallet.name))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let names = pallets
+        .iter()
+        .map(|pallet| &pallet.name)
+        .collect::<Vec<_>>();
+    let indices = pallets
+        .iter()
+        .map(|pallet| Literal::u8_unsuffixed(pallet.index))
+        .collect::<Vec<_>>();
+    Ok(quote! {
+        /// All pallets of the runtime with their index as discriminant
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[repr(u8)]
+        pub enum #name {
+            #( #variants = #indices ),*
+        }
+
+        impl #name {
+            /// All pallets, in declaration order
+            pub const ALL: &'static [#name] = &[ #( #name::#variants ),* ];
+
+            /// The name of the pallet in the runtime
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #( #name::#variants => #names, )*
+                }
+            }
+
+            /// The index of the pallet, the first byte of its encoded calls
+            pub fn index(&self) -> u8 {
+                *self as u8
+            }
+
+            /// The pallet with the given index
+            pub fn from_index(index: u8) -> Option<Self> {
+                match index {
+                    #( #indices => Some(#name::#variants), )*
+                    _ => None,
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_construct_runtime() {
+        let pallets = construct_runtime_pallets(
+            r#"
+            construct_runtime!(
+                pub enum Runtime where
+                    Block = Block,
+                    NodeBlock = opaque::Block,
+                    UncheckedExtrinsic = UncheckedExtrinsic
+                {
+                    System: frame_system::{Pallet, Call, Config, Storage, Event<T>} = 0,
+                    Timestamp: pallet_timestamp,
+                    #[cfg(feature = "balances")]
+                    Balances: pallet_balances = 5,
+                    Sudo: pallet_sudo,
+                }
+            );
+        "#,
+        )
+        .unwrap();
+        let indices = pallets
+            .iter()
+            .map(|pallet| (pallet.name.as_str(), pallet.index))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            indices,
+            vec![
+                ("System", 0),
+                ("Timestamp", 1),
+                ("Balances", 5),
+                ("Sudo", 6)
+            ]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! JSON Schema of the serde representation of the generated enum
+
+use crate::{type_to_string, ExpandedCall};
+use serde_json::{json, Map, Value};
+use syn::{Expr, Fields, GenericArgument, Lit, PathArguments, Type};
+
+/// Creates a schema for the default, externally tagged serde representation
+/// of the generated enum
+pub(crate) fn call_schema(call: &ExpandedCall) -> Value {
+    let variants = call
+        .variants
+        .iter()
+        .map(|v| {
+            let name = v.variant.ident.to_string();
+            let payload = match &v.variant.fields {
+                Fields::Unit => return json!({ "const": name }),
+                Fields::Named(fields) => {
+                    let mut properties = Map::new();
+                    let mut required = Vec::new();
+                    for field in fields.named.iter() {
+                        let ident = field.ident.as_ref().unwrap().to_string();
+                        properties.insert(ident.clone(), type_schema(&field.ty));
+                        required.push(Value::String(ident));
+                    }
+                    json!({
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                        "additionalProperties": false,
+                    })
+                }
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    type_schema(&fields.unnamed[0].ty)
+                }
+                Fields::Unnamed(fields) => {
+                    tuple_schema(fields.unnamed.iter().map(|field| &field.ty))
+                }
+            };
+            json!({
+                "type": "object",
+                "properties": { name.clone(): payload },
+                "required": [name],
+                "additionalProperties": false,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": call.name.to_string(),
+        "oneOf": variants,
+    })
+}
+
+/// Creates the schema of a type, types that are not known are accepted as
+/// any value and described by their path
+fn type_schema(ty: &Type) -> Value {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last().unwrap();
+            let inner = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                }),
+                _ => None,
+            };
+            match (segment.ident.to_string().as_str(), inner) {
+                ("bool", _) => json!({ "type": "boolean" }),
+                ("String", _) => json!({ "type": "string" }),
+                ("char", _) => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+                ("u8", _) => integer(0, u8::MAX as u64),
+                ("u16", _) => integer(0, u16::MAX as u64),
+                ("u32", _) => integer(0, u32::MAX as u64),
+                ("u64", _) => integer(0, u64::MAX),
+                ("u128", _) | ("usize", _) => json!({ "type": "integer", "minimum": 0 }),
+                ("i8", _) => integer(i8::MIN as i64, i8::MAX as i64),
+                ("i16", _) => integer(i16::MIN as i64, i16::MAX as i64),
+                ("i32", _) => integer(i32::MIN as i64, i32::MAX as i64),
+                ("i64", _) => integer(i64::MIN, i64::MAX),
+                ("i128", _) | ("isize", _) => json!({ "type": "integer" }),
+                ("Vec", Some(inner))
+                | ("BoundedVec", Some(inner))
+                | ("WeakBoundedVec", Some(inner)) => {
+                    json!({ "type": "array", "items": type_schema(inner) })
+                }
+                ("Option", Some(inner)) => {
+                    json!({ "anyOf": [type_schema(inner), { "type": "null" }] })
+                }
+                ("Box", Some(inner)) | ("Compact", Some(inner)) => type_schema(inner),
+                _ => unknown(ty),
+            }
+        }
+        Type::Array(array) => {
+            let mut schema = json!({ "type": "array", "items": type_schema(&array.elem) });
+            if let Expr::Lit(lit) = &array.len {
+                if let Lit::Int(len) = &lit.lit {
+                    if let Ok(len) = len.base10_parse::<u64>() {
+                        schema["minItems"] = len.into();
+                        schema["maxItems"] = len.into();
+                    }
+                }
+            }
+            schema
+        }
+        Type::Tuple(tuple) if tuple.elems.is_empty() => json!({ "type": "null" }),
+        Type::Tuple(tuple) => tuple_schema(tuple.elems.iter()),
+        Type::Paren(paren) => type_schema(&paren.elem),
+        Type::Group(group) => type_schema(&group.elem),
+        _ => unknown(ty),
+    }
+}
+
+/// Creates the schema of a fixed size array of the types
+fn tuple_schema<'a>(types: impl Iterator<Item = &'a Type>) -> Value {
+    let items = types.map(type_schema).collect::<Vec<_>>();
+    json!({
+        "type": "array",
+        "items": items,
+        "minItems": items.len(),
+        "maxItems": items.len(),
+    })
+}
+
+fn integer(min: impl Into<Value>, max: impl Into<Value>) -> Value {
+    let (min, max): (Value, Value) = (min.into(), max.into());
+    json!({ "type": "integer", "minimum": min, "maximum": max })
+}
+
+fn unknown(ty: &Type) -> Value {
+    json!({ "description": type_to_string(ty) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_create_type_schemas() {
+        let schema = |ty: &str| type_schema(&syn::parse_str(ty).unwrap());
+        assert_eq!(
+            schema("u8"),
+            json!({ "type": "integer", "minimum": 0, "maximum": 255 })
+        );
+        assert_eq!(
+            schema("Vec<bool>"),
+            json!({ "type": "array", "items": { "type": "boolean" } })
+        );
+        assert_eq!(schema("[u8; 2]")["maxItems"], json!(2));
+        assert_eq!(
+            schema("Option<codec::Compact<String>>"),
+            json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] })
+        );
+        assert_eq!(
+            schema("sp_runtime::AccountId32"),
+            json!({ "description": "sp_runtime::AccountId32" })
+        );
+    }
+}
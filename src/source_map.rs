@@ -0,0 +1,145 @@
+//! Maps the generated variants and fields back to the `pallet::Call`
+
+use crate::ExpandedCall;
+use proc_macro2::Span;
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::Fields;
+
+/// The locations of all generated variants and fields in the parsed
+/// `pallet::Call`, as exported by `PalletCall::to_source_map`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceMap {
+    /// The version of the format
+    pub version: u32,
+    /// The file the `pallet::Call` was parsed from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// The name of the generated enum
+    pub name: String,
+    /// All variants of the generated enum
+    pub variants: Vec<VariantSource>,
+}
+
+impl SourceMap {
+    /// The current version of the format
+    pub const VERSION: u32 = 1;
+
+    pub(crate) fn new(call: &ExpandedCall, file: Option<String>) -> Self {
+        let variants = call
+            .variants
+            .iter()
+            .map(|v| {
+                let original = match &v.original.fields {
+                    Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+                    Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
+                    Fields::Unit => Vec::new(),
+                };
+                let fields = v
+                    .variant
+                    .fields
+                    .iter()
+                    .zip(original)
+                    .enumerate()
+                    .map(|(idx, (field, original))| FieldSource {
+                        field: field
+                            .ident
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or_else(|| idx.to_string()),
+                        span: SourceSpan::new(original.span()),
+                    })
+                    .collect();
+                VariantSource {
+                    variant: v.variant.ident.to_string(),
+                    call: v.call_name.clone(),
+                    span: SourceSpan::new(v.original.span()),
+                    fields,
+                }
+            })
+            .collect();
+        SourceMap {
+            version: Self::VERSION,
+            file,
+            name: call.name.to_string(),
+            variants,
+        }
+    }
+}
+
+/// The location of a generated variant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariantSource {
+    /// The name of the generated variant, like `Transfer`
+    pub variant: String,
+    /// The original name of the call, like `transfer`
+    pub call: String,
+    /// The location of the original variant
+    pub span: SourceSpan,
+    /// The locations of all fields of the generated variant
+    pub fields: Vec<FieldSource>,
+}
+
+/// The location of a field of a generated variant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldSource {
+    /// The name of the generated field, or its position for unnamed fields
+    pub field: String,
+    /// The location of the original field
+    pub span: SourceSpan,
+}
+
+/// A range in the parsed source, lines are 1-based and columns are 0-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpan {
+    /// The line of the first character
+    pub start_line: usize,
+    /// The column of the first character
+    pub start_column: usize,
+    /// The line after the last character
+    pub end_line: usize,
+    /// The column after the last character
+    pub end_column: usize,
+}
+
+impl SourceSpan {
+    fn new(span: Span) -> Self {
+        let (start, end) = (span.start(), span.end());
+        SourceSpan {
+            start_line: start.line,
+            start_column: start.column,
+            end_line: end.line,
+            end_column: end.column,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PalletCallConfig;
+
+    #[test]
+    fn can_map_variants_to_their_source() {
+        let map = PalletCallConfig::default()
+            .parse(
+                "pub enum Call<T: Config> {
+    transfer(T::AccountId, #[codec(compact)] T::Balance),
+    remark(Vec<u8>),
+}",
+            )
+            .unwrap()
+            .source_map(Some("balances.rs".to_string()))
+            .unwrap();
+
+        assert_eq!(map.file.as_deref(), Some("balances.rs"));
+        let remark = &map.variants[1];
+        assert_eq!(remark.variant, "Remark");
+        assert_eq!(remark.span.start_line, 3);
+        assert_eq!(remark.span.start_column, 4);
+        let balance = &map.variants[0].fields[1];
+        assert_eq!(balance.field, "1");
+        assert_eq!(balance.span.start_line, 2);
+        assert_eq!(balance.span.start_column, 27);
+        assert_eq!(balance.span.end_column, 55);
+    }
+}
@@ -0,0 +1,997 @@
+//! The passes that rewrite a parsed `pallet::Call`
+//!
+//! Expansion is a pipeline of built-in passes, see [`Pass`]. Additional
+//! [`CallTransform`]s can be inserted after each of them with
+//! `PalletCallConfig::transform_after`.
+
+use crate::{
+    attributes, docs, options, CallOptions, CallWeight, CompactMode, Error, OriginKind,
+    PalletCallConfig, ParameterStyle, ReferenceMode, Result, VariantInfo, Warning, WarningKind,
+};
+use crate::{call_signature, ensure_no_std, parse_ident, type_to_string, Location};
+use inflector::Inflector;
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use std::collections::BTreeMap;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    AngleBracketedGenericArguments, Attribute, Data, DeriveInput, Field, Fields, FieldsNamed,
+    FieldsUnnamed, GenericArgument, GenericParam, Generics, Ident, Lifetime, LifetimeDef, Path,
+    PathArguments, PathSegment, Type, TypePath, TypeReference, Variant,
+};
+
+/// A step of the expansion that rewrites the `ExpandedCall`
+///
+/// This is implemented for all functions with a matching signature.
+///
+/// # Example
+///
+/// ```no_run
+/// # use substrate_pallet_call_extract::{ExpandedCall, PalletCallConfig, Pass, Result};
+/// // remove all calls without parameters
+/// let expanded = PalletCallConfig::default()
+///     .transform_after(
+///         Pass::SkipCalls,
+///         |_: &PalletCallConfig, call: &mut ExpandedCall| -> Result<()> {
+///             call.variants.retain(|v| !v.original.fields.is_empty());
+///             Ok(())
+///         },
+///     )
+///     .parse_pallet("pallet-balances")
+///     .unwrap();
+/// ```
+pub trait CallTransform: Send + Sync {
+    /// Rewrites the call
+    fn transform(&self, config: &PalletCallConfig, call: &mut ExpandedCall) -> Result<()>;
+}
+
+impl<F> CallTransform for F
+where
+    F: Fn(&PalletCallConfig, &mut ExpandedCall) -> Result<()> + Send + Sync,
+{
+    fn transform(&self, config: &PalletCallConfig, call: &mut ExpandedCall) -> Result<()> {
+        (self)(config, call)
+    }
+}
+
+/// The built-in passes of the expansion, in the order they're run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pass {
+    /// Removes all calls that are not included
+    SkipCalls,
+    /// Sets the names of the variants and the style of their fields
+    Rename,
+    /// Replaces all types that refer to `T: Config` with generics or
+    /// substitutions
+    EraseGenerics,
+    /// Rewrites the attributes and docs of the variants and their fields
+    FilterAttributes,
+    /// Sets the derives and attributes of the enum
+    Derives,
+}
+
+impl Pass {
+    /// All passes in the order they're run
+    pub const ALL: [Pass; 5] = [
+        Pass::SkipCalls,
+        Pass::Rename,
+        Pass::EraseGenerics,
+        Pass::FilterAttributes,
+        Pass::Derives,
+    ];
+
+    pub(crate) fn run(self, config: &PalletCallConfig, call: &mut ExpandedCall) -> Result<()> {
+        match self {
+            Pass::SkipCalls => skip_calls(config, call),
+            Pass::Rename => rename(config, call),
+            Pass::EraseGenerics => erase_generics(config, call),
+            Pass::FilterAttributes => filter_attributes(config, call),
+            Pass::Derives => derives(config, call),
+        }
+    }
+}
+
+/// A `pallet::Call` that is rewritten by the passes before it's emitted
+#[derive(Debug, Clone)]
+pub struct ExpandedCall {
+    /// The name of the generated enum
+    pub name: Ident,
+    /// The attributes of the generated enum, including its derives
+    pub attrs: Vec<Attribute>,
+    /// All introduced generics by their original type path
+    ///
+    /// If the `T: Config` generic is kept, these are only emitted as
+    /// `<Generic>Of<T>` aliases.
+    pub generics: BTreeMap<String, String>,
+    /// The original type paths of the generics, by their string
+    pub(crate) types: BTreeMap<String, TypePath>,
+    /// The variants of the generated enum
+    pub variants: Vec<ExpandedVariant>,
+    /// All warnings of the expansion
+    pub warnings: Vec<Warning>,
+    /// The generics of the `pallet::Call`, like `<T: Config>`
+    pub(crate) input_generics: Generics,
+    /// The variant that is skipped by the codec, like `__Ignore`
+    pub(crate) skipped: Option<Ident>,
+    /// The named lifetimes of the fields, declared by the generated enum
+    pub(crate) lifetimes: Vec<Lifetime>,
+}
+
+impl ExpandedCall {
+    /// The generics of the `pallet::Call` with the lifetimes of the fields
+    /// that it doesn't declare already
+    pub(crate) fn declared_generics(&self) -> Generics {
+        let mut generics = self.input_generics.clone();
+        let declared = generics
+            .lifetimes()
+            .map(|def| def.lifetime.clone())
+            .collect::<Vec<_>>();
+        let missing = self
+            .lifetimes
+            .iter()
+            .filter(|lifetime| !declared.contains(lifetime))
+            .map(|lifetime| GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())))
+            .collect::<Vec<_>>();
+        for (idx, param) in missing.into_iter().enumerate() {
+            generics.params.insert(idx, param);
+        }
+        generics
+    }
+}
+
+/// A variant of the `ExpandedCall`
+#[derive(Debug, Clone)]
+pub struct ExpandedVariant {
+    /// The original name of the call
+    pub call_name: String,
+    /// The index of the call
+    pub index: u8,
+    /// The variant of the `pallet::Call`
+    pub original: Variant,
+    /// The generated variant
+    pub variant: Variant,
+    /// The origin the call requires, if it's classified from the pallet
+    /// source
+    pub origin: Option<OriginKind>,
+    /// The benchmarked weight of the call, if it's parsed from the pallet's
+    /// `weights.rs`
+    pub weight: Option<CallWeight>,
+}
+
+impl ExpandedCall {
+    /// Creates the call with all variants of the `pallet::Call` as they are,
+    /// only the `__Ignore` variant is removed
+    pub(crate) fn new(input: &DeriveInput, options: &CallOptions) -> Result<Self> {
+        let data = match &input.data {
+            Data::Enum(data) => data,
+            _ => {
+                return Err(syn::Error::new(input.span(), "Expected a `Call` enum").into());
+            }
+        };
+
+        // the name of the final call enum
+        let name = options.name.as_deref().unwrap_or("Call");
+        let mut name = options::parse_config("name", name, syn::parse_str::<Ident>)?;
+        name.set_span(input.ident.span());
+
+        let is_ignore = |v: &&Variant| v.ident.to_string().to_lowercase() == "__ignore";
+        let skipped = data
+            .variants
+            .iter()
+            .take_while(is_ignore)
+            .last()
+            .map(|v| v.ident.clone());
+
+        let mut variants = Vec::with_capacity(data.variants.len());
+        let mut errors = Vec::new();
+        for (position, variant) in data
+            .variants
+            .iter()
+            // skip the `__ignore` variant, which is also marked `[codec(skip)]`
+            .skip_while(is_ignore)
+            .enumerate()
+        {
+            match attributes::call_index(&variant.attrs, position) {
+                Ok(index) => variants.push(ExpandedVariant {
+                    // the name of `r#type` is `type`
+                    call_name: variant.ident.unraw().to_string(),
+                    index,
+                    original: variant.clone(),
+                    variant: remove_skipped_fields(variant.clone()),
+                    origin: None,
+                    weight: None,
+                }),
+                Err(err) => errors.push(err.into()),
+            }
+        }
+        Error::combine(errors)?;
+
+        Ok(ExpandedCall {
+            name,
+            attrs: Vec::new(),
+            generics: BTreeMap::new(),
+            types: BTreeMap::new(),
+            variants,
+            warnings: Vec::new(),
+            input_generics: input.generics.clone(),
+            skipped,
+            lifetimes: Vec::new(),
+        })
+    }
+}
+
+/// Removes all fields marked `#[codec(skip)]`, they're not encoded
+fn remove_skipped_fields(mut variant: Variant) -> Variant {
+    let fields = match &mut variant.fields {
+        Fields::Named(fields) => &mut fields.named,
+        Fields::Unnamed(fields) => &mut fields.unnamed,
+        Fields::Unit => return variant,
+    };
+    *fields = std::mem::take(fields)
+        .into_iter()
+        .filter(|field| !field.attrs.iter().any(attributes::is_skip))
+        .collect();
+    variant
+}
+
+/// Removes all calls that are not included
+fn skip_calls(config: &PalletCallConfig, call: &mut ExpandedCall) -> Result<()> {
+    let warnings = &mut call.warnings;
+    call.variants.retain(|v| {
+        if config.options.is_call_included(&v.call_name) {
+            return true;
+        }
+        warnings.push(Warning::new(
+            WarningKind::SkippedCall,
+            &v.call_name,
+            "Call is excluded".to_string(),
+            v.original.ident.span(),
+        ));
+        false
+    });
+    Ok(())
+}
+
+/// Sets the names of the variants and the style of their fields
+///
+/// Fails if multiple calls would be emitted with the same variant name.
+fn rename(config: &PalletCallConfig, call: &mut ExpandedCall) -> Result<()> {
+    let mut errors = Vec::new();
+    // the original call names by variant name
+    let mut names = BTreeMap::<String, String>::new();
+    for v in call.variants.iter_mut() {
+        let call_override = config.options.overrides.get(&v.call_name);
+        let variant_name = call_override
+            .and_then(|o| o.name.clone())
+            .or_else(|| {
+                config
+                    .variant_name_conversion
+                    .as_ref()
+                    .map(|c| (c)(&v.call_name))
+            })
+            .unwrap_or_else(|| v.call_name.to_pascal_case());
+        match options::parse_config("variant name", &variant_name, parse_ident) {
+            Ok(mut ident) => {
+                ident.set_span(v.original.ident.span());
+                v.variant.ident = ident;
+            }
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        }
+        let name = v.variant.ident.unraw().to_string();
+        if let Some(other) = names.get(&name) {
+            errors.push(Error::Collision {
+                name,
+                originals: vec![other.clone(), v.call_name.clone()],
+                location: Location::new(v.original.ident.span()),
+            });
+        } else {
+            names.insert(name, v.call_name.clone());
+        }
+
+        // keep the delimiters of the original fields
+        let (span, fields) = match std::mem::replace(&mut v.variant.fields, Fields::Unit) {
+            Fields::Named(fields) => (fields.brace_token.span, fields.named),
+            Fields::Unnamed(fields) => (fields.paren_token.span, fields.unnamed),
+            Fields::Unit => continue,
+        };
+        if fields.is_empty() && config.options.unit_variants {
+            continue;
+        }
+        let style = call_override
+            .and_then(|o| o.parameter_style)
+            .unwrap_or(config.options.parameter_style);
+        v.variant.fields = match style {
+            ParameterStyle::Unnamed => Fields::Unnamed(FieldsUnnamed {
+                paren_token: syn::token::Paren(span),
+                unnamed: fields
+                    .into_iter()
+                    .map(|mut field| {
+                        field.ident = None;
+                        field.colon_token = None;
+                        field
+                    })
+                    .collect(),
+            }),
+            ParameterStyle::Named => Fields::Named(FieldsNamed {
+                brace_token: syn::token::Brace(span),
+                named: fields
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, mut field)| {
+                        // TODO derive the name from the dispatchable's parameters
+                        let span = field.ty.span();
+                        if field.ident.is_none() {
+                            field.ident = Some(format_ident!("arg{}", idx, span = span));
+                        }
+                        field.colon_token = Some(syn::token::Colon(span));
+                        field
+                    })
+                    .collect(),
+            }),
+        };
+    }
+    Error::combine(errors)
+}
+
+/// Replaces all types that refer to the type parameters of the `pallet::Call`
+/// with either a substitution or a new generic
+///
+/// Fails with the errors of all fields.
+fn erase_generics(config: &PalletCallConfig, call: &mut ExpandedCall) -> Result<()> {
+    let substitutions = config.options.parse_substitutions()?;
+    let type_paths = config.options.parse_type_paths()?;
+    let opaque = config.options.parse_opaque_type()?;
+    // only collect the generics but keep the original types
+    let keep = config.options.keep_generics;
+    let type_params = call
+        .input_generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    let ExpandedCall {
+        generics,
+        types,
+        variants,
+        lifetimes,
+        warnings,
+        ..
+    } = call;
+
+    let mut errors = Vec::new();
+    // the positions of the calls that are skipped in lenient mode
+    let mut invalid = Vec::new();
+    for (position, v) in variants.iter_mut().enumerate() {
+        let errors_before = errors.len();
+        let snapshot = if config.options.lenient {
+            Some((generics.clone(), types.clone(), lifetimes.clone()))
+        } else {
+            None
+        };
+        let call_substitutions = match config
+            .options
+            .overrides
+            .get(&v.call_name)
+            .map(|o| options::parse_types(&o.substitutions))
+            .transpose()
+        {
+            Ok(call_substitutions) => call_substitutions,
+            Err(err) => {
+                errors.push(err);
+                None
+            }
+        };
+        for (idx, field) in fields_mut(&mut v.variant).enumerate() {
+            if let Some(ty) = config.type_rewrite.as_ref().and_then(|f| (f)(&field.ty)) {
+                field.ty = ty;
+            }
+            if !type_paths.is_empty() {
+                map_type_paths(&mut field.ty, &type_paths);
+            }
+            // whether the field is a `Vec<T>` of an owned `&[T]`
+            let mut owned_slice = false;
+            if let Type::Reference(reference) = &mut field.ty {
+                if references_type_params(&reference.elem, &type_params) {
+                    match config.options.references {
+                        ReferenceMode::Borrowed if reference.lifetime.is_none() => {
+                            let span = reference.and_token.spans[0];
+                            reference.lifetime = Some(Lifetime::new("'a", span));
+                        }
+                        ReferenceMode::Borrowed => {}
+                        ReferenceMode::Owned => {
+                            owned_slice = matches!(*reference.elem, Type::Slice(_));
+                            field.ty = owned_type(reference);
+                        }
+                    }
+                }
+            }
+            let lifetimes_before = lifetimes.len();
+            if !collect_lifetimes(&field.ty, lifetimes) {
+                if let Some(opaque) = opaque.as_ref() {
+                    lifetimes.truncate(lifetimes_before);
+                    make_opaque(field, opaque);
+                    continue;
+                }
+                errors.push(Error::ElidedLifetime {
+                    call: v.call_name.clone(),
+                    field: field
+                        .ident
+                        .as_ref()
+                        .map(|ident| ident.unraw().to_string())
+                        .unwrap_or_else(|| idx.to_string()),
+                    location: Location::new(field.ty.span()),
+                });
+                continue;
+            }
+            // the generic of `&T::AccountId` or `&[T::Hash]` is the referenced type
+            let field_ty = match &mut field.ty {
+                Type::Reference(reference) => match &mut *reference.elem {
+                    Type::Slice(slice) => &mut *slice.elem,
+                    elem => elem,
+                },
+                Type::Path(path) if owned_slice => vec_elem(path).expect("owned slice is a `Vec`"),
+                ty => ty,
+            };
+            let path = if let Type::Path(path) = &*field_ty {
+                path
+            } else if keep || !references_type_params(&field.ty, &type_params) {
+                // types like `[u8; 32]` are kept as they are
+                continue;
+            } else if let Some(opaque) = opaque.as_ref() {
+                make_opaque(field, opaque);
+                continue;
+            } else {
+                errors.push(Error::UnsupportedType {
+                    call: v.call_name.clone(),
+                    ty: type_to_string(&field.ty),
+                    location: Location::new(field.ty.span()),
+                });
+                continue;
+            };
+            if !references_type_params(path, &type_params) {
+                continue;
+            }
+
+            // generic type
+            let ty_str = quote!(#path).to_string();
+            let generic_ty = generics.get(&ty_str).cloned().unwrap_or_else(|| {
+                config
+                    .generic_name_conversion
+                    .as_ref()
+                    .map(|c| (c)(path))
+                    .unwrap_or_else(|| {
+                        let mut ty = path.path.segments.last().unwrap().clone();
+                        ty.ident = ty.ident.unraw();
+                        quote!(#ty).to_string()
+                    })
+            });
+            let substitution = call_substitutions
+                .as_ref()
+                .and_then(|s| s.get(generic_ty.as_str()))
+                .or_else(|| substitutions.get(generic_ty.as_str()));
+            if let Some(ty) = substitution.filter(|_| !keep) {
+                // use the concrete type instead of introducing a generic
+                *field_ty = respanned(ty, path.span());
+                continue;
+            }
+            if let Some((other, _)) = generics
+                .iter()
+                .find(|(ty, name)| **name == generic_ty && **ty != ty_str)
+            {
+                errors.push(Error::Collision {
+                    name: generic_ty,
+                    originals: vec![other.replace(' ', ""), ty_str.replace(' ', "")],
+                    location: Location::new(path.span()),
+                });
+                continue;
+            }
+            types.entry(ty_str.clone()).or_insert_with(|| path.clone());
+            if keep {
+                generics.insert(ty_str, generic_ty);
+                continue;
+            }
+            // create a new field with the generic as type, that points to the
+            // original type
+            let mut ident = match options::parse_config("generic name", &generic_ty, parse_ident) {
+                Ok(ident) => ident,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            };
+            ident.set_span(path.span());
+            *field_ty = generic_type(ident);
+            generics.insert(ty_str, generic_ty);
+        }
+
+        if let Some((prev_generics, prev_types, prev_lifetimes)) = snapshot {
+            if errors.len() > errors_before {
+                // skip the call and drop the generics it introduced
+                *generics = prev_generics;
+                *types = prev_types;
+                *lifetimes = prev_lifetimes;
+                for err in errors.drain(errors_before..) {
+                    warnings.push(Warning::new(
+                        WarningKind::InvalidCall,
+                        &v.call_name,
+                        format!("Call is skipped: {}", err),
+                        v.original.ident.span(),
+                    ));
+                }
+                invalid.push(position);
+            }
+        }
+    }
+    let mut position = 0;
+    variants.retain(|_| {
+        let valid = !invalid.contains(&position);
+        position += 1;
+        valid
+    });
+    Error::combine(errors)
+}
+
+/// Replaces the type of an unsupported field with the opaque type and notes
+/// the original type in its docs
+fn make_opaque(field: &mut Field, opaque: &Type) {
+    let note = format!(
+        " Opaque: the original type `{}` is not supported",
+        type_to_string(&field.ty)
+    );
+    field.attrs.push(syn::parse_quote!(#[doc = #note]));
+    field.ty = respanned(opaque, field.ty.span());
+}
+
+/// Replaces all paths of the type that are in `type_paths`, the generic
+/// arguments of a replaced path are kept
+fn map_type_paths(ty: &mut Type, type_paths: &BTreeMap<String, Path>) {
+    match ty {
+        Type::Path(ty) => {
+            if let Some(qself) = ty.qself.as_mut() {
+                map_type_paths(&mut qself.ty, type_paths);
+            }
+            for segment in ty.path.segments.iter_mut() {
+                if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in args.args.iter_mut() {
+                        if let GenericArgument::Type(ty) = arg {
+                            map_type_paths(ty, type_paths);
+                        }
+                    }
+                }
+            }
+            if let Some(replacement) = type_paths.get(&options::path_key(&ty.path)) {
+                let arguments = ty.path.segments.last().map(|s| s.arguments.clone());
+                let mut replacement = replacement.clone();
+                if let (Some(last), Some(arguments)) = (replacement.segments.last_mut(), arguments)
+                {
+                    if last.arguments.is_empty() {
+                        last.arguments = arguments;
+                    }
+                }
+                ty.path = replacement;
+            }
+        }
+        Type::Array(ty) => map_type_paths(&mut ty.elem, type_paths),
+        Type::Group(ty) => map_type_paths(&mut ty.elem, type_paths),
+        Type::Paren(ty) => map_type_paths(&mut ty.elem, type_paths),
+        Type::Ptr(ty) => map_type_paths(&mut ty.elem, type_paths),
+        Type::Reference(ty) => map_type_paths(&mut ty.elem, type_paths),
+        Type::Slice(ty) => map_type_paths(&mut ty.elem, type_paths),
+        Type::Tuple(ty) => {
+            for elem in ty.elems.iter_mut() {
+                map_type_paths(elem, type_paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The owned type of a reference, `&[T]` becomes `Vec<T>`
+fn owned_type(reference: &TypeReference) -> Type {
+    match &*reference.elem {
+        Type::Slice(slice) => {
+            let elem = &slice.elem;
+            let ty: Type = syn::parse_quote!(Vec<#elem>);
+            respanned(&ty, slice.bracket_token.span)
+        }
+        elem => elem.clone(),
+    }
+}
+
+/// The element type of a `Vec<T>`
+fn vec_elem(path: &mut TypePath) -> Option<&mut Type> {
+    match &mut path.path.segments.last_mut()?.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first_mut()? {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Sets the span of all tokens of the type, so that diagnostics of a type
+/// that replaces a field type point at the original field
+pub(crate) fn respanned(ty: &Type, span: Span) -> Type {
+    syn::parse2(respan(ty.to_token_stream(), span)).expect("respanned type is valid")
+}
+
+/// Sets the span of all tokens
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = TokenTree::Group(respanned);
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
+}
+
+/// Creates the type of a generic, like `Balance`
+pub(crate) fn generic_type(ident: Ident) -> Type {
+    Type::Path(TypePath {
+        qself: None,
+        path: Path::from(ident),
+    })
+}
+
+/// Wraps the type in `<codec>::Compact<ty>`
+fn compact_type(codec_crate: &Path, ty: Type) -> Type {
+    let span = ty.span();
+    let mut path = codec_crate.clone();
+    path.segments.push(PathSegment {
+        ident: Ident::new("Compact", span),
+        arguments: PathArguments::AngleBracketed(AngleBracketedGenericArguments {
+            colon2_token: None,
+            lt_token: Default::default(),
+            args: std::iter::once(GenericArgument::Type(ty)).collect(),
+            gt_token: Default::default(),
+        }),
+    });
+    Type::Path(TypePath { qself: None, path })
+}
+
+/// Rewrites the attributes and docs of the variants and their fields
+fn filter_attributes(config: &PalletCallConfig, call: &mut ExpandedCall) -> Result<()> {
+    let options = &config.options;
+    let codec_crate = options.parse_codec_crate()?;
+    if options.without_codec && options.compact == CompactMode::Wrap {
+        return Err(Error::Config(
+            "compact fields can't be wrapped in `Compact` without codec".to_string(),
+        ));
+    }
+    let warnings = &mut call.warnings;
+
+    for v in call.variants.iter_mut() {
+        let variant_name = v.variant.ident.clone();
+        let info = VariantInfo {
+            call_name: &v.call_name,
+            variant_name: &variant_name,
+            index: v.index,
+            variant: &v.original,
+        };
+
+        for field in fields_mut(&mut v.variant) {
+            if options.without_codec {
+                field.attrs.retain(|attr| !attr.path.is_ident("codec"));
+            }
+            attributes::apply_cfg_policy(&mut field.attrs, &options.cfg)?;
+            retain_allowed(options, &mut field.attrs, &v.call_name, warnings);
+            match options.compact {
+                CompactMode::Keep => {}
+                CompactMode::Strip => field.attrs.retain(|attr| !attributes::is_compact(attr)),
+                CompactMode::Wrap => {
+                    if field.attrs.iter().any(attributes::is_compact) {
+                        field.attrs.retain(|attr| !attributes::is_compact(attr));
+                        let ty =
+                            std::mem::replace(&mut field.ty, Type::Verbatim(TokenStream::new()));
+                        field.ty = compact_type(&codec_crate, ty);
+                    }
+                }
+            }
+            if let Some(field_attributes) = config.field_attributes.as_ref() {
+                field.attrs = (field_attributes)(field, &info);
+            }
+        }
+
+        let attrs = &mut v.variant.attrs;
+        if options.without_codec {
+            attrs.retain(|attr| !attr.path.is_ident("codec"));
+        }
+        attributes::apply_cfg_policy(attrs, &options.cfg)?;
+        retain_allowed(options, attrs, &v.call_name, warnings);
+        if let Some(docs) = config.docs.as_ref() {
+            let lines = (docs)(&attributes::doc_lines(attrs), &info);
+            attributes::remove_doc_attributes(attrs);
+            attrs.splice(0..0, attributes::doc_attributes(&lines));
+        } else if !options.keep_comments && !options.call_docs && options.docs_source.is_none() {
+            if let Some(doc) = attrs.iter().find(|attr| attr.path.is_ident("doc")) {
+                warnings.push(Warning::new(
+                    WarningKind::DroppedDocs,
+                    &v.call_name,
+                    "Doc comments are removed".to_string(),
+                    doc.span(),
+                ));
+            }
+            attributes::remove_doc_attributes(attrs);
+        }
+        if !attrs.iter().any(|attr| attr.path.is_ident("deprecated")) {
+            // calls can also be marked deprecated by convention in their docs
+            if let Some(note) =
+                attributes::deprecation_note(&attributes::doc_lines(&v.original.attrs))
+            {
+                attrs.push(if note.is_empty() {
+                    syn::parse_quote!(#[deprecated])
+                } else {
+                    syn::parse_quote!(#[deprecated(note = #note)])
+                });
+            }
+        }
+        if options.signature_docs {
+            if attrs.iter().any(|attr| attr.path.is_ident("doc")) {
+                attrs.extend(attributes::doc_attributes(&[String::new()]));
+            }
+            attrs.extend(attributes::doc_attributes(&[format!(
+                " origin call: `{}`",
+                call_signature(&v.original)
+            )]));
+        }
+        if let Some(call_override) = options.overrides.get(&v.call_name) {
+            attrs.extend(call_override.parse_attributes()?);
+        }
+        if options.call_docs || options.docs_source.is_some() {
+            let args = docs::argument_docs(&attributes::doc_lines(&v.original.attrs));
+            for field in v.variant.fields.iter_mut() {
+                let doc = match field
+                    .ident
+                    .as_ref()
+                    .and_then(|ident| args.get(&ident.unraw().to_string()))
+                {
+                    Some(doc) => format!(" {}", doc),
+                    None => continue,
+                };
+                if !field.attrs.iter().any(|attr| attr.path.is_ident("doc")) {
+                    field.attrs.insert(0, syn::parse_quote!(#[doc = #doc]));
+                }
+            }
+        }
+        if options.is_call_hidden(&v.call_name) {
+            attrs.push(syn::parse_quote!(#[doc(hidden)]));
+        }
+        if let Some(variant_attributes) = config.variant_attributes.as_ref() {
+            attrs.extend((variant_attributes)(&info));
+        }
+    }
+    Ok(())
+}
+
+/// Sets the derives and attributes of the enum
+fn derives(config: &PalletCallConfig, call: &mut ExpandedCall) -> Result<()> {
+    let options = &config.options;
+    if options.without_codec
+        && (options.decode_with_mem_tracking
+            || options.max_encoded_len
+            || options.encode_bound.is_some()
+            || options.decode_bound.is_some()
+            || options.dumb_trait_bound)
+    {
+        return Err(Error::Config(
+            "codec derives and bounds can't be emitted without codec".to_string(),
+        ));
+    }
+    let mut base_derives = options.parse_base_derives()?;
+    if options.decode_with_mem_tracking {
+        let codec_crate = options.parse_codec_crate()?;
+        base_derives.push(syn::parse_quote!(#codec_crate::DecodeWithMemTracking));
+    }
+    if options.max_encoded_len {
+        let codec_crate = options.parse_codec_crate()?;
+        base_derives.push(syn::parse_quote!(#codec_crate::MaxEncodedLen));
+    }
+    let additional_derives = options.parse_derives()?;
+    let mut additional_attr = options.parse_attributes()?;
+    additional_attr.retain(|attr| options.is_attribute_allowed(&attr.path));
+    if options.no_std {
+        let variants = call.variants.iter().map(|v| &v.variant);
+        ensure_no_std(&quote! {
+            #( #variants )*
+            #( #base_derives )*
+            #( #additional_derives )*
+            #( #additional_attr )*
+        })?;
+    }
+
+    if !base_derives.is_empty() || !additional_derives.is_empty() {
+        call.attrs.push(syn::parse_quote! {
+            #[derive(
+                #( #base_derives, )*
+                #( #additional_derives ), *
+            )]
+        });
+    }
+    call.attrs.extend(options.parse_codec_bounds()?);
+    if let Some(runtime_debug) = options.runtime_debug.as_ref() {
+        let runtime_debug = options::parse_config(
+            "runtime debug crate",
+            &format!("{}::RuntimeDebug", runtime_debug),
+            syn::parse_str::<Path>,
+        )?;
+        call.attrs
+            .push(syn::parse_quote!(#[derive(#runtime_debug)]));
+    }
+    let std_derives = options.parse_std_derives()?;
+    if !std_derives.is_empty() {
+        call.attrs.push(syn::parse_quote! {
+            #[cfg_attr(feature = "std", derive( #( #std_derives ),* ))]
+        });
+    }
+    call.attrs.extend(additional_attr);
+    if options.non_exhaustive {
+        call.attrs.push(syn::parse_quote!(#[non_exhaustive]));
+    }
+    if options.doc_hidden {
+        call.attrs.push(syn::parse_quote!(#[doc(hidden)]));
+    }
+    if options.allow_missing_docs {
+        call.attrs.push(syn::parse_quote!(#[allow(missing_docs)]));
+    }
+    Ok(())
+}
+
+/// Removes all attributes that are not allowed and records a warning for each
+/// of them
+fn retain_allowed(
+    options: &CallOptions,
+    attrs: &mut Vec<Attribute>,
+    call: &str,
+    warnings: &mut Vec<Warning>,
+) {
+    attrs.retain(|attr| {
+        if options.is_attribute_allowed(&attr.path) {
+            return true;
+        }
+        let path = &attr.path;
+        let (kind, message) = if path.is_ident("doc") {
+            (
+                WarningKind::DroppedDocs,
+                "Doc comments are removed".to_string(),
+            )
+        } else {
+            (
+                WarningKind::DroppedAttribute,
+                format!(
+                    "Attribute `{}` is removed",
+                    quote!(#path).to_string().replace(' ', "")
+                ),
+            )
+        };
+        warnings.push(Warning::new(kind, call, message, attr.span()));
+        false
+    });
+}
+
+/// Returns all fields of the variant
+pub(crate) fn fields_mut(variant: &mut Variant) -> impl Iterator<Item = &mut Field> {
+    let fields = match &mut variant.fields {
+        Fields::Named(fields) => Some(fields.named.iter_mut()),
+        Fields::Unnamed(fields) => Some(fields.unnamed.iter_mut()),
+        Fields::Unit => None,
+    };
+    fields.into_iter().flatten()
+}
+
+/// Collects the named lifetimes of the type, like `'a` of `&'a [u8]`
+///
+/// Returns `false` if the type contains a reference with an elided lifetime.
+fn collect_lifetimes(ty: &Type, lifetimes: &mut Vec<Lifetime>) -> bool {
+    fn visit(tokens: TokenStream, lifetimes: &mut Vec<Lifetime>) -> bool {
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                    if let Some(TokenTree::Ident(ident)) = tokens.next() {
+                        if ident == "_" {
+                            return false;
+                        }
+                        let lifetime = Lifetime {
+                            apostrophe: punct.span(),
+                            ident,
+                        };
+                        if lifetime.ident != "static" && !lifetimes.contains(&lifetime) {
+                            lifetimes.push(lifetime);
+                        }
+                    }
+                }
+                TokenTree::Punct(punct) if punct.as_char() == '&' => match tokens.peek() {
+                    Some(TokenTree::Punct(next)) if next.as_char() == '\'' => {}
+                    _ => return false,
+                },
+                TokenTree::Group(group) if !visit(group.stream(), lifetimes) => return false,
+                _ => {}
+            }
+        }
+        true
+    }
+    visit(quote!(#ty), lifetimes)
+}
+
+/// Whether the type refers to any of the type parameters, like `T::Balance`
+/// or `<T as Config>::Balance`
+fn references_type_params(ty: &impl ToTokens, type_params: &[Ident]) -> bool {
+    fn visit(tokens: TokenStream, type_params: &[Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => type_params.contains(&ident),
+            TokenTree::Group(group) => visit(group.stream(), type_params),
+            _ => false,
+        })
+    }
+    visit(quote!(#ty), type_params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_detect_type_params() {
+        let params = vec![format_ident!("T")];
+        let referenced =
+            |ty: &str| references_type_params(&syn::parse_str::<Type>(ty).unwrap(), &params);
+        assert!(referenced("T::Balance"));
+        assert!(referenced("<T as Config>::Balance"));
+        assert!(referenced("Vec<T::AccountId>"));
+        assert!(!referenced("Vec<u8>"));
+        assert!(referenced("[T::Hash; 2]"));
+        assert!(!referenced("[u8; 32]"));
+    }
+
+    #[test]
+    fn can_collect_lifetimes() {
+        let collect = |ty: &str| {
+            let mut lifetimes = Vec::new();
+            collect_lifetimes(&syn::parse_str(ty).unwrap(), &mut lifetimes).then(|| {
+                lifetimes
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(collect("Cow<'a, [&'b u8]>").unwrap(), vec!["'a", "'b"]);
+        assert_eq!(collect("&'static [u8]").unwrap(), Vec::<String>::new());
+        assert!(collect("&[u8]").is_none());
+        assert!(collect("Cow<'_, [u8]>").is_none());
+    }
+
+    #[test]
+    fn can_preserve_spans() {
+        let call = PalletCallConfig::default()
+            .substitute("Balance", syn::parse_quote!(u128))
+            .parse(
+                "pub enum Call<T: Config> {
+    transfer(
+        T::AccountId,
+        #[codec(compact)] T::Balance,
+    ),
+}",
+            )
+            .unwrap()
+            .expand_call()
+            .unwrap();
+        let variant = &call.variants[0].variant;
+        assert_eq!(variant.ident.span().start().line, 2);
+        let lines = variant
+            .fields
+            .iter()
+            .map(|field| field.ty.span().start().line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [3, 4]);
+    }
+}
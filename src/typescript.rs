@@ -0,0 +1,134 @@
+//! TypeScript definitions of the serde representation of the generated enum
+
+use crate::{type_to_string, ExpandedCall};
+use syn::{Fields, GenericArgument, PathArguments, Type};
+
+/// Creates a `.d.ts` module with a tagged union for the default, externally
+/// tagged serde representation of the generated enum
+pub(crate) fn call_definitions(call: &ExpandedCall) -> String {
+    let variants = call
+        .variants
+        .iter()
+        .map(|v| {
+            let name = v.variant.ident.to_string();
+            let payload = match &v.variant.fields {
+                Fields::Unit => return format!("\"{}\"", name),
+                Fields::Named(fields) => {
+                    let properties = fields
+                        .named
+                        .iter()
+                        .map(|field| {
+                            format!(
+                                "{}: {}",
+                                field.ident.as_ref().unwrap(),
+                                type_definition(&field.ty)
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    format!("{{ {} }}", properties.join("; "))
+                }
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    type_definition(&fields.unnamed[0].ty)
+                }
+                Fields::Unnamed(fields) => {
+                    tuple_definition(fields.unnamed.iter().map(|field| &field.ty))
+                }
+            };
+            format!("{{ {}: {} }}", name, payload)
+        })
+        .collect::<Vec<_>>();
+
+    let mut definitions = format!("export type {} =", call.name);
+    if variants.is_empty() {
+        definitions.push_str(" never");
+    }
+    for variant in variants {
+        definitions.push_str("\n  | ");
+        definitions.push_str(&variant);
+    }
+    definitions.push_str(";\n");
+    definitions
+}
+
+/// Returns the TypeScript type of a type, types that are not known are
+/// `unknown` and described by their path
+fn type_definition(ty: &Type) -> String {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last().unwrap();
+            let inner = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                }),
+                _ => None,
+            };
+            match (segment.ident.to_string().as_str(), inner) {
+                ("bool", _) => "boolean".to_string(),
+                ("String", _) | ("char", _) => "string".to_string(),
+                ("u8", _)
+                | ("u16", _)
+                | ("u32", _)
+                | ("u64", _)
+                | ("u128", _)
+                | ("usize", _)
+                | ("i8", _)
+                | ("i16", _)
+                | ("i32", _)
+                | ("i64", _)
+                | ("i128", _)
+                | ("isize", _) => "number".to_string(),
+                ("Vec", Some(inner))
+                | ("BoundedVec", Some(inner))
+                | ("WeakBoundedVec", Some(inner)) => array_definition(inner),
+                ("Option", Some(inner)) => format!("{} | null", type_definition(inner)),
+                ("Box", Some(inner)) | ("Compact", Some(inner)) => type_definition(inner),
+                _ => unknown(ty),
+            }
+        }
+        Type::Array(array) => array_definition(&array.elem),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => "null".to_string(),
+        Type::Tuple(tuple) => tuple_definition(tuple.elems.iter()),
+        Type::Paren(paren) => type_definition(&paren.elem),
+        Type::Group(group) => type_definition(&group.elem),
+        _ => unknown(ty),
+    }
+}
+
+/// Returns the type of an array of the type, like `number[]`
+fn array_definition(elem: &Type) -> String {
+    let elem = type_definition(elem);
+    if elem.contains(" | ") {
+        format!("({})[]", elem)
+    } else {
+        format!("{}[]", elem)
+    }
+}
+
+/// Returns the type of a fixed size array of the types, like `[number, string]`
+fn tuple_definition<'a>(types: impl Iterator<Item = &'a Type>) -> String {
+    let items = types.map(type_definition).collect::<Vec<_>>();
+    format!("[{}]", items.join(", "))
+}
+
+fn unknown(ty: &Type) -> String {
+    format!("unknown /* {} */", type_to_string(ty))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_create_type_definitions() {
+        let definition = |ty: &str| type_definition(&syn::parse_str(ty).unwrap());
+        assert_eq!(definition("u128"), "number");
+        assert_eq!(definition("Vec<Option<bool>>"), "(boolean | null)[]");
+        assert_eq!(definition("(u8, String)"), "[number, string]");
+        assert_eq!(definition("codec::Compact<u32>"), "number");
+        assert_eq!(
+            definition("sp_runtime::AccountId32"),
+            "unknown /* sp_runtime::AccountId32 */"
+        );
+    }
+}
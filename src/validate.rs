@@ -0,0 +1,306 @@
+//! A validation pass over the generated enum, before any tokens are emitted
+
+use crate::{attributes, options, Error, Location, PalletCall, Result};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{Data, Ident, Path, Visibility};
+
+/// A problem of the generated enum found by `PalletCall::validate`
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// The category of the problem
+    pub kind: FindingKind,
+    /// The original name of the affected call, if the problem is specific to
+    /// a call
+    pub call: Option<String>,
+    /// A description of the problem
+    pub message: String,
+    /// The location of the problem in the `pallet::Call`
+    pub location: Location,
+}
+
+impl Finding {
+    fn new(kind: FindingKind, call: Option<&str>, message: String, span: Span) -> Self {
+        Finding {
+            kind,
+            call: call.map(str::to_string),
+            message,
+            location: Location::new(span),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.call.as_ref() {
+            Some(call) => write!(f, "{} in call `{}`: {}", self.kind, call, self.message),
+            None => write!(f, "{}: {}", self.kind, self.message),
+        }
+    }
+}
+
+/// The categories of findings of the validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FindingKind {
+    /// A field still refers to a generic of the `pallet::Call`, or has a type
+    /// that can't be turned into a generic
+    UnresolvedGeneric,
+    /// Multiple variants or generics would have the same name
+    DuplicateName,
+    /// Multiple calls are encoded with the same index
+    DuplicateIndex,
+    /// A call index doesn't fit in a `u8`
+    IndexOutOfRange,
+    /// A configured or generated name is not a valid identifier
+    InvalidIdent,
+    /// A configured derive is not a valid path
+    InvalidDerive,
+    /// Any other error of the expansion
+    Invalid,
+}
+
+impl fmt::Display for FindingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            FindingKind::UnresolvedGeneric => "unresolved generic",
+            FindingKind::DuplicateName => "duplicate name",
+            FindingKind::DuplicateIndex => "duplicate index",
+            FindingKind::IndexOutOfRange => "index out of range",
+            FindingKind::InvalidIdent => "invalid identifier",
+            FindingKind::InvalidDerive => "invalid derive",
+            FindingKind::Invalid => "invalid",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Validates the configuration and the call indices first, the expansion is
+/// only validated if they're valid, since it fails for the same reasons.
+pub(crate) fn validate(pallet: &PalletCall) -> Vec<Finding> {
+    let mut findings = validate_config(pallet);
+    findings.extend(validate_indices(pallet));
+    if findings.is_empty() {
+        findings.extend(validate_expansion(pallet));
+    }
+    findings
+}
+
+fn validate_config(pallet: &PalletCall) -> Vec<Finding> {
+    let options = pallet.config.options();
+    let mut findings = Vec::new();
+    let mut check = |kind: FindingKind, result: Result<()>| {
+        if let Err(err) = result {
+            for err in err.into_errors() {
+                findings.push(Finding::new(kind, None, err.to_string(), Span::call_site()));
+            }
+        }
+    };
+
+    let name = options.name.as_deref().unwrap_or("Call");
+    check(
+        FindingKind::InvalidIdent,
+        options::parse_config("name", name, syn::parse_str::<Ident>).map(drop),
+    );
+    if let Some(module) = options.module.as_deref() {
+        check(
+            FindingKind::InvalidIdent,
+            options::parse_config("module", module, syn::parse_str::<Ident>).map(drop),
+        );
+    }
+    let vis = options.visibility.as_deref().unwrap_or("pub");
+    check(
+        FindingKind::InvalidIdent,
+        options::parse_config("visibility", vis, syn::parse_str::<Visibility>).map(drop),
+    );
+    for derive in options
+        .derives
+        .iter()
+        .chain(options.base_derives.iter().flatten())
+        .chain(options.std_derives.iter())
+    {
+        check(
+            FindingKind::InvalidDerive,
+            options::parse_config("derive", derive, syn::parse_str::<Path>).map(drop),
+        );
+    }
+    findings
+}
+
+/// Checks the raw `#[codec(index = ..)]` of every call, which are rejected
+/// without the offending value by the expansion
+fn validate_indices(pallet: &PalletCall) -> Vec<Finding> {
+    let data = match &pallet.input.data {
+        Data::Enum(data) => data,
+        _ => return Vec::new(),
+    };
+    let mut findings = Vec::new();
+    let mut indices = BTreeMap::<u64, String>::new();
+    for (position, variant) in data
+        .variants
+        .iter()
+        .skip_while(|v| v.ident.to_string().to_lowercase() == "__ignore")
+        .enumerate()
+    {
+        let call = variant.ident.unraw().to_string();
+        let (index, span) = match attributes::explicit_index(&variant.attrs) {
+            Some(lit) => match lit.base10_parse::<u64>() {
+                Ok(index) => (index, lit.span()),
+                Err(err) => {
+                    findings.push(Finding::new(
+                        FindingKind::IndexOutOfRange,
+                        Some(&call),
+                        err.to_string(),
+                        lit.span(),
+                    ));
+                    continue;
+                }
+            },
+            None => (position as u64, variant.ident.span()),
+        };
+        if index > u8::MAX as u64 {
+            findings.push(Finding::new(
+                FindingKind::IndexOutOfRange,
+                Some(&call),
+                format!("the index {} exceeds the maximum of 255", index),
+                span,
+            ));
+        } else if let Some(other) = indices.insert(index, call.clone()) {
+            findings.push(Finding::new(
+                FindingKind::DuplicateIndex,
+                Some(&call),
+                format!("the index {} is also used by `{}`", index, other),
+                span,
+            ));
+        }
+    }
+    findings
+}
+
+fn validate_expansion(pallet: &PalletCall) -> Vec<Finding> {
+    let call = match pallet.expand_unvalidated() {
+        Ok(call) => call,
+        Err(err) => return err.into_errors().into_iter().map(from_error).collect(),
+    };
+
+    let mut findings = Vec::new();
+    let mut names = BTreeSet::new();
+    for v in &call.variants {
+        if !names.insert(v.variant.ident.to_string()) {
+            findings.push(Finding::new(
+                FindingKind::DuplicateName,
+                Some(&v.call_name),
+                format!("the variant `{}` is emitted twice", v.variant.ident),
+                v.original.ident.span(),
+            ));
+        }
+    }
+    for generic in call.generics.values() {
+        if syn::parse_str::<Ident>(generic).is_err() {
+            findings.push(Finding::new(
+                FindingKind::InvalidIdent,
+                None,
+                format!("the generic `{}` is not a valid identifier", generic),
+                Span::call_site(),
+            ));
+        }
+    }
+    if !pallet.config.options().keep_generics {
+        let params = call
+            .input_generics
+            .type_params()
+            .map(|param| param.ident.clone())
+            .collect::<Vec<_>>();
+        for v in &call.variants {
+            for field in v.variant.fields.iter() {
+                let ty = &field.ty;
+                if let Some(param) = find_ident(quote!(#ty), &params) {
+                    findings.push(Finding::new(
+                        FindingKind::UnresolvedGeneric,
+                        Some(&v.call_name),
+                        format!(
+                            "the field type `{}` still refers to `{}`",
+                            crate::type_to_string(ty),
+                            param
+                        ),
+                        field.ty.span(),
+                    ));
+                }
+            }
+        }
+    }
+    findings
+}
+
+/// Turns an error of the expansion into a finding
+fn from_error(err: Error) -> Finding {
+    let span = err.span();
+    match err {
+        Error::UnsupportedType { ref call, .. } | Error::ElidedLifetime { ref call, .. } => {
+            Finding::new(
+                FindingKind::UnresolvedGeneric,
+                Some(call),
+                err.to_string(),
+                span,
+            )
+        }
+        Error::Collision { .. } => {
+            Finding::new(FindingKind::DuplicateName, None, err.to_string(), span)
+        }
+        Error::Warning(ref warning) => Finding::new(
+            FindingKind::Invalid,
+            Some(&warning.call),
+            err.to_string(),
+            span,
+        ),
+        err => Finding::new(FindingKind::Invalid, None, err.to_string(), span),
+    }
+}
+
+/// Returns the first of the identifiers that occurs in the tokens
+fn find_ident(tokens: TokenStream, idents: &[Ident]) -> Option<Ident> {
+    tokens.into_iter().find_map(|tree| match tree {
+        TokenTree::Ident(ident) => idents.iter().find(|i| **i == ident).cloned(),
+        TokenTree::Group(group) => find_ident(group.stream(), idents),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PalletCallConfig;
+
+    #[test]
+    fn can_validate_indices() {
+        let findings = PalletCallConfig::default()
+            .name("Not a name")
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                #[codec(index = 1)]
+                transfer(T::AccountId, T::Balance),
+                #[codec(index = 1)]
+                remark(Vec<u8>),
+                #[codec(index = 300)]
+                set_code(Vec<u8>),
+            }
+        "#,
+            )
+            .unwrap()
+            .validate();
+        let kinds = findings.iter().map(|f| f.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                FindingKind::InvalidIdent,
+                FindingKind::DuplicateIndex,
+                FindingKind::IndexOutOfRange
+            ]
+        );
+        assert_eq!(findings[1].call.as_deref(), Some("remark"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Emits conversions of the generic `Call` enum to and from
+//! `scale_value::Value`
+
+use crate::concrete::pattern;
+use crate::{parse_ident, ExpandedCall, Result};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Fields, Variant, Visibility};
+
+/// The feature of the consuming crate the conversions are gated behind
+const FEATURE: &str = "scale-value";
+
+/// Emits `to_value` and `from_value` for the generic enum
+///
+/// The value of a call is a variant with the original name of the call, like
+/// `transfer`, and its parameters. Parameters are converted with
+/// `scale_value::serde`, so the generics need to implement `Serialize` and
+/// `Deserialize`.
+pub(crate) fn emit(
+    call: &ExpandedCall,
+    variants: &[Variant],
+    vis: &Visibility,
+) -> Result<TokenStream> {
+    let name = &call.name;
+    let generics = call
+        .generics
+        .values()
+        .map(|generic| parse_ident(generic))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let (ty_generics, ser_generics, de_generics) = if generics.is_empty() {
+        (quote! {}, quote! {}, quote! {})
+    } else {
+        (
+            quote! { <#( #generics ),*> },
+            quote! { <#( #generics: serde::Serialize ),*> },
+            quote! { <#( #generics: serde::de::DeserializeOwned ),*> },
+        )
+    };
+
+    let mut to_value = Vec::with_capacity(variants.len());
+    let mut from_value = Vec::with_capacity(variants.len());
+    for (v, variant) in call.variants.iter().zip(variants) {
+        let ident = &variant.ident;
+        let call_name = &v.call_name;
+        let pattern = pattern(&variant.fields);
+        let bindings = bindings(&variant.fields);
+        to_value.push(match &variant.fields {
+            Fields::Named(_) => {
+                let names = bindings.iter().map(|b| b.to_string());
+                quote! {
+                    #name::#ident #pattern => scale_value::Value::named_variant(
+                        #call_name,
+                        vec![#( (#names, scale_value::serde::to_value(#bindings)?) ),*],
+                    )
+                }
+            }
+            _ => quote! {
+                #name::#ident #pattern => scale_value::Value::unnamed_variant(
+                    #call_name,
+                    vec![#( scale_value::serde::to_value(#bindings)? ),*],
+                )
+            },
+        });
+        from_value.push(quote! {
+            #call_name => {
+                #( let #bindings = scale_value::serde::from_value(
+                    values.next().ok_or_else(|| missing(#call_name))?,
+                )?; )*
+                #name::#ident #pattern
+            }
+        });
+    }
+
+    Ok(quote! {
+        #[cfg(feature = #FEATURE)]
+        impl #ser_generics #name #ty_generics {
+            /// Converts the call into a dynamic `scale_value::Value`, a variant
+            /// with the name of the call and its parameters
+            #vis fn to_value(&self) -> Result<scale_value::Value, scale_value::serde::SerializerError> {
+                Ok(match self {
+                    #( #to_value, )*
+                })
+            }
+        }
+
+        #[cfg(feature = #FEATURE)]
+        impl #de_generics #name #ty_generics {
+            /// Converts a dynamic `scale_value::Value`, like the one created by
+            /// `to_value`, back into the call
+            #vis fn from_value(value: scale_value::Value) -> Result<Self, scale_value::serde::DeserializerError> {
+                use serde::de::Error;
+                #[allow(unused_variables)]
+                let missing = |call: &str| {
+                    scale_value::serde::DeserializerError::custom(
+                        format!("missing parameter of call `{}`", call),
+                    )
+                };
+                let variant = match value.value {
+                    scale_value::ValueDef::Variant(variant) => variant,
+                    _ => return Err(scale_value::serde::DeserializerError::custom("expected a variant")),
+                };
+                #[allow(unused_mut, unused_variables)]
+                let mut values = variant.values.into_values();
+                Ok(match variant.name.as_str() {
+                    #( #from_value, )*
+                    name => {
+                        return Err(scale_value::serde::DeserializerError::custom(
+                            format!("unknown call `{}`", name),
+                        ))
+                    }
+                })
+            }
+        }
+    })
+}
+
+/// The names the fields are bound to by `pattern`
+pub(crate) fn bindings(fields: &Fields) -> Vec<syn::Ident> {
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .filter_map(|f| f.ident.clone())
+            .collect(),
+        Fields::Unnamed(fields) => (0..fields.unnamed.len())
+            .map(|idx| format_ident!("f{}", idx))
+            .collect(),
+        Fields::Unit => Vec::new(),
+    }
+}
@@ -0,0 +1,145 @@
+//! Verifies that the generated code compiles by running `cargo check` in a
+//! temporary crate
+
+use crate::PalletCall;
+use anyhow::Context;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Distinguishes the temporary crates of concurrent verifications
+static CRATES: AtomicUsize = AtomicUsize::new(0);
+
+/// Checks the generated code with `cargo check`, before it lands in the
+/// user's build
+///
+/// The generated code is written to a temporary crate together with the
+/// compile-pass test of `PalletCall::to_compile_test`, which instantiates
+/// every call with a stub type for all generics.
+///
+/// # Example
+///
+/// ```no_run
+/// # use substrate_pallet_call_extract::{PalletCallConfig, Verifier};
+/// let call = PalletCallConfig::default()
+///     .push_import(syn::parse_quote!(use sp_std::prelude::*;))
+///     .parse_pallet("pallet-balances")
+///     .unwrap();
+/// Verifier::default()
+///     .dependency("sp-std", r#""4.0.0""#)
+///     .verify(&call)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Verifier {
+    /// The dependencies of the temporary crate, by name
+    dependencies: BTreeMap<String, String>,
+    /// The target directory of `cargo check`, shared between verifications
+    target_dir: Option<PathBuf>,
+}
+
+impl Default for Verifier {
+    fn default() -> Self {
+        Verifier {
+            dependencies: BTreeMap::new(),
+            target_dir: None,
+        }
+        .dependency(
+            "codec",
+            r#"{ package = "parity-scale-codec", version = "3", features = ["derive"] }"#,
+        )
+    }
+}
+
+impl Verifier {
+    /// Add a dependency to the temporary crate, `spec` is the value of the
+    /// dependency in a `Cargo.toml`, like `"1.0"` or `{ version = "1.0" }`
+    pub fn dependency(mut self, name: impl Into<String>, spec: impl Into<String>) -> Self {
+        self.dependencies.insert(name.into(), spec.into());
+        self
+    }
+
+    /// The target directory to build the temporary crate in, by default a
+    /// directory in the system's temp dir
+    pub fn target_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.target_dir = Some(dir.into());
+        self
+    }
+
+    /// Runs `cargo check` over the generated code of the call
+    ///
+    /// Fails with the diagnostics of cargo if the code doesn't compile.
+    pub fn verify(&self, call: &PalletCall) -> anyhow::Result<()> {
+        let generated = call.to_source()?;
+        let main = call.to_compile_test("generated.rs")?;
+
+        let dir = std::env::temp_dir().join(format!(
+            "pallet-call-extract-verify-{}-{}",
+            std::process::id(),
+            CRATES.fetch_add(1, Ordering::SeqCst)
+        ));
+        let result = self.check(&dir, &generated, &main);
+        let _ = std::fs::remove_dir_all(&dir);
+        result
+    }
+
+    fn check(&self, dir: &Path, generated: &str, main: &str) -> anyhow::Result<()> {
+        let src = dir.join("src");
+        std::fs::create_dir_all(&src)
+            .with_context(|| format!("Failed to create {}", src.display()))?;
+        std::fs::write(dir.join("Cargo.toml"), self.manifest())?;
+        std::fs::write(src.join("generated.rs"), generated)?;
+        std::fs::write(src.join("main.rs"), main)?;
+
+        let target_dir = self
+            .target_dir
+            .clone()
+            .unwrap_or_else(|| std::env::temp_dir().join("pallet-call-extract-verify-target"));
+        let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let output = Command::new(cargo)
+            .arg("check")
+            .arg("--quiet")
+            .arg("--message-format=short")
+            .arg("--manifest-path")
+            .arg(dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(target_dir)
+            .output()
+            .context("Failed to run `cargo check`")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "The generated code doesn't compile:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(())
+    }
+
+    /// The `Cargo.toml` of the temporary crate, an empty `[workspace]` keeps
+    /// it out of any enclosing workspace
+    fn manifest(&self) -> String {
+        let mut manifest = String::from(
+            "[package]\nname = \"pallet-call-extract-verify\"\nversion = \"0.0.0\"\nedition = \"2018\"\n\n[workspace]\n\n[dependencies]\n",
+        );
+        for (name, spec) in &self.dependencies {
+            manifest.push_str(&format!("{} = {}\n", name, spec));
+        }
+        manifest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_render_manifest() {
+        let manifest = Verifier::default()
+            .dependency("sp-std", r#""4.0.0""#)
+            .manifest();
+        assert!(manifest.contains("[workspace]"));
+        assert!(manifest.contains("\nsp-std = \"4.0.0\"\n"));
+        assert!(manifest.contains("codec = { package = \"parity-scale-codec\""));
+    }
+}
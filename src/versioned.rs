@@ -0,0 +1,232 @@
+//! Expand multiple versions of the same pallet, with conversions between
+//! consecutive versions
+
+use crate::{output, parse_ident, Error, ExpandedCall, PalletCall, Result, WriteMode};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::BTreeSet;
+use syn::{Fields, Variant};
+
+/// Multiple versions of the same pallet call, each emitted as its own enum
+///
+/// For every two consecutive versions a `TryFrom` conversion from the older
+/// into the newer enum is emitted. Calls with the same name and the same
+/// fields in both versions are converted, all others return the older call
+/// as error. This allows indexers to decode historical blocks with the enum
+/// of their runtime version and handle them as the latest version where
+/// possible.
+///
+/// # Example
+///
+/// ```no_run
+/// # use substrate_pallet_call_extract::{PalletCallConfig, VersionedCalls};
+/// let config = PalletCallConfig::default();
+/// let versions = VersionedCalls::default()
+///     .push("CallV1", config.clone().parse(std::fs::read_to_string("v1.rs").unwrap()).unwrap())
+///     .push("CallV2", config.parse(std::fs::read_to_string("v2.rs").unwrap()).unwrap());
+/// let expanded = versions.expand().unwrap();
+/// ```
+#[derive(Default)]
+pub struct VersionedCalls {
+    /// All versions by the name of their enum, oldest first
+    versions: Vec<(String, PalletCall<'static>)>,
+}
+
+impl VersionedCalls {
+    /// Add the next version of the pallet call, emitted as an enum with the
+    /// given name
+    pub fn push(mut self, name: impl Into<String>, call: PalletCall<'_>) -> Self {
+        self.versions.push((name.into(), call.into_owned()));
+        self
+    }
+
+    /// Expands all versions and the conversions between them
+    pub fn expand(&self) -> Result<TokenStream> {
+        let mut calls = Vec::with_capacity(self.versions.len());
+        let mut errors = Vec::new();
+        for (name, pallet) in &self.versions {
+            if pallet.config.options.keep_generics {
+                errors.push(Error::Config(format!(
+                    "version `{}` keeps the `Config` generic, conversions require erased generics",
+                    name
+                )));
+                continue;
+            }
+            match pallet.expand_call().and_then(|mut call| {
+                call.name = parse_ident(name)?;
+                Ok(call)
+            }) {
+                Ok(call) => calls.push(call),
+                Err(err) => errors.push(err),
+            }
+        }
+        Error::combine(errors)?;
+        let mut names = BTreeSet::new();
+        for call in &calls {
+            if !names.insert(call.name.to_string()) {
+                return Err(Error::Config(format!(
+                    "multiple versions are named `{}`",
+                    call.name
+                )));
+            }
+        }
+
+        let mut items = Vec::with_capacity(calls.len() * 2);
+        for ((_, pallet), call) in self.versions.iter().zip(&calls) {
+            items.push(pallet.emit_enum(call)?);
+        }
+        for pair in calls.windows(2) {
+            items.push(emit_conversion(&pair[0], &pair[1])?);
+        }
+        let tokens = quote! { #( #items )* };
+        match self.versions.first() {
+            Some((_, pallet)) => pallet.wrap(None, tokens),
+            None => Ok(tokens),
+        }
+    }
+
+    /// Expands all versions and writes the generated code to the given path
+    ///
+    /// Returns `true` if the file was written.
+    pub fn write_to_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        mode: WriteMode,
+    ) -> anyhow::Result<bool> {
+        let options = self.versions.first().map(|(_, call)| call.config.options());
+        let content = output::finish(output::render(self.expand()?)?, options)?;
+        Ok(output::write(path.as_ref(), &content, mode)?)
+    }
+}
+
+/// Emits `TryFrom<old> for new`, which converts all calls that are unchanged
+fn emit_conversion(old: &ExpandedCall, new: &ExpandedCall) -> Result<TokenStream> {
+    let old_generics = generics(old)?;
+    let new_generics = generics(new)?;
+    let mut impl_generics = old_generics.clone();
+    for generic in &new_generics {
+        if !impl_generics.contains(generic) {
+            impl_generics.push(generic.clone());
+        }
+    }
+    let lifetimes = old
+        .lifetimes
+        .iter()
+        .chain(new.lifetimes.iter().filter(|l| !old.lifetimes.contains(l)));
+    let old_lifetimes = &old.lifetimes;
+    let new_lifetimes = &new.lifetimes;
+
+    let old_name = &old.name;
+    let new_name = &new.name;
+    let old_ty = quote! { #old_name<#( #old_lifetimes, )* #( #old_generics ),*> };
+    let new_ty = quote! { #new_name<#( #new_lifetimes, )* #( #new_generics ),*> };
+
+    let arms = old.variants.iter().filter_map(|old_variant| {
+        let new_variant = new
+            .variants
+            .iter()
+            .find(|v| v.call_name == old_variant.call_name)?;
+        if signature(&old_variant.variant) != signature(&new_variant.variant) {
+            return None;
+        }
+        let old_ident = &old_variant.variant.ident;
+        let new_ident = &new_variant.variant.ident;
+        let pattern = crate::concrete::pattern(&old_variant.variant.fields);
+        Some(quote! {
+            #old_name::#old_ident #pattern => Ok(#new_name::#new_ident #pattern),
+        })
+    });
+
+    Ok(quote! {
+        impl<#( #lifetimes, )* #( #impl_generics ),*> core::convert::TryFrom<#old_ty> for #new_ty {
+            type Error = #old_ty;
+
+            fn try_from(call: #old_ty) -> Result<Self, Self::Error> {
+                #[allow(unreachable_patterns)]
+                match call {
+                    #( #arms )*
+                    call => Err(call),
+                }
+            }
+        }
+    })
+}
+
+/// The names of the generics of the call
+fn generics(call: &ExpandedCall) -> Result<Vec<syn::Ident>> {
+    Ok(call
+        .generics
+        .values()
+        .map(|generic| parse_ident(generic))
+        .collect::<syn::Result<Vec<_>>>()?)
+}
+
+/// The names and types of the fields, which must match to convert a call
+fn signature(variant: &Variant) -> Vec<String> {
+    let fields = match &variant.fields {
+        Fields::Named(fields) => &fields.named,
+        Fields::Unnamed(fields) => &fields.unnamed,
+        Fields::Unit => return Vec::new(),
+    };
+    fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let compact = field.attrs.iter().any(crate::attributes::is_compact);
+            quote! { #ident #ty #compact }.to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PalletCallConfig;
+
+    #[test]
+    fn can_convert_unchanged_calls() {
+        let v1 = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, #[codec(compact)] T::Balance),
+                remark(Vec<u8>),
+            }
+        "#,
+            )
+            .unwrap();
+        let v2 = PalletCallConfig::default()
+            .parse(
+                r#"
+            pub enum Call<T: Config> {
+                transfer(T::AccountId, T::Balance),
+                remark(Vec<u8>),
+            }
+        "#,
+            )
+            .unwrap();
+
+        let expanded = VersionedCalls::default()
+            .push("CallV1", v1)
+            .push("CallV2", v2)
+            .expand()
+            .unwrap();
+        let conversion = quote! {
+            impl<AccountId, Balance> core::convert::TryFrom<CallV1<AccountId, Balance> >
+                for CallV2<AccountId, Balance>
+            {
+                type Error = CallV1<AccountId, Balance>;
+
+                fn try_from(call: CallV1<AccountId, Balance>) -> Result<Self, Self::Error> {
+                    #[allow(unreachable_patterns)]
+                    match call {
+                        CallV1::Remark(f0) => Ok(CallV2::Remark(f0)),
+                        call => Err(call),
+                    }
+                }
+            }
+        };
+        assert!(expanded.to_string().ends_with(&conversion.to_string()));
+    }
+}
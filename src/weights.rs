@@ -0,0 +1,209 @@
+//! Static weight estimates from a benchmark generated `weights.rs`
+
+use crate::{Error, Result};
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use syn::ext::IdentExt;
+use syn::{ImplItem, Item, LitInt, Type};
+
+/// The benchmarked weight of a call, without the components that depend on
+/// its parameters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CallWeight {
+    /// The base execution time, in picoseconds
+    pub ref_time: u64,
+    /// The base proof size, in bytes
+    pub proof_size: u64,
+    /// The number of database reads
+    pub reads: u64,
+    /// The number of database writes
+    pub writes: u64,
+}
+
+/// Parses the weights of all functions of the `WeightInfo` implementation in
+/// a benchmark generated `weights.rs`, by function name
+///
+/// The implementation for `SubstrateWeight<T>` is preferred over others,
+/// like the one for `()`. The base weight is read from
+/// `Weight::from_parts(ref_time, proof_size)` or
+/// `Weight::from_ref_time(ref_time)`, database accesses from constant
+/// `reads(..)` and `writes(..)`. Components that depend on a parameter of the
+/// function are ignored.
+pub fn parse_weights(source: &str) -> Result<BTreeMap<String, CallWeight>> {
+    let file = syn::parse_file(source)?;
+    let impls = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Impl(item) => item
+                .trait_
+                .as_ref()
+                .filter(|(_, path, _)| path.is_ident("WeightInfo"))
+                .map(|_| item),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let is_substrate_weight = |ty: &Type| match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "SubstrateWeight")
+            .unwrap_or_default(),
+        _ => false,
+    };
+    let item = match impls
+        .iter()
+        .find(|item| is_substrate_weight(&item.self_ty))
+        .or_else(|| impls.first())
+    {
+        Some(item) => item,
+        None => return Ok(BTreeMap::new()),
+    };
+
+    let mut weights = BTreeMap::new();
+    for item in &item.items {
+        if let ImplItem::Method(method) = item {
+            let mut weight = CallWeight::default();
+            let mut base = false;
+            visit(method.block.to_token_stream(), &mut weight, &mut base);
+            weights.insert(method.sig.ident.unraw().to_string(), weight);
+        }
+    }
+    Ok(weights)
+}
+
+/// Reads the `weights.rs` from the file and parses it, see
+/// [`parse_weights`]
+pub(crate) fn parse_weights_from_path(path: &Path) -> Result<BTreeMap<String, CallWeight>> {
+    let source = std::fs::read_to_string(path).map_err(|err| {
+        Error::Config(format!(
+            "failed to read weights {}: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    parse_weights(&source)
+}
+
+/// Collects the base weight and the constant database accesses of the tokens
+fn visit(tokens: TokenStream, weight: &mut CallWeight, base: &mut bool) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let ident = match token {
+            TokenTree::Group(group) => {
+                visit(group.stream(), weight, base);
+                continue;
+            }
+            TokenTree::Ident(ident) => ident.to_string(),
+            _ => continue,
+        };
+        let args = match tokens.peek() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                constant_args(group.stream())
+            }
+            _ => continue,
+        };
+        match (ident.as_str(), args.as_slice()) {
+            ("from_parts", [Some(ref_time), Some(proof_size)]) if !*base => {
+                weight.ref_time = *ref_time;
+                weight.proof_size = *proof_size;
+                *base = true;
+            }
+            ("from_ref_time", [Some(ref_time)]) if !*base => {
+                weight.ref_time = *ref_time;
+                *base = true;
+            }
+            ("reads", [Some(reads)]) => weight.reads += reads,
+            ("writes", [Some(writes)]) => weight.writes += writes,
+            _ => {}
+        }
+    }
+}
+
+/// The comma separated arguments, `Some` for integer constants like
+/// `1_000` or `1 as u64`
+fn constant_args(tokens: TokenStream) -> Vec<Option<u64>> {
+    let mut args = vec![Vec::new()];
+    for token in tokens {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => args.push(Vec::new()),
+            token => args.last_mut().unwrap().push(token),
+        }
+    }
+    args.into_iter()
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| match arg.as_slice() {
+            [TokenTree::Literal(lit)] => LitInt::from(lit.clone()).base10_parse().ok(),
+            [TokenTree::Literal(lit), TokenTree::Ident(as_), TokenTree::Ident(_)]
+                if as_ == "as" =>
+            {
+                LitInt::from(lit.clone()).base10_parse().ok()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_weights() {
+        let weights = parse_weights(
+            r#"
+            pub trait WeightInfo {
+                fn transfer_allow_death() -> Weight;
+                fn upgrade_accounts(u: u32) -> Weight;
+            }
+
+            pub struct SubstrateWeight<T>(PhantomData<T>);
+            impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+                fn transfer_allow_death() -> Weight {
+                    Weight::from_parts(38_480_000, 3593)
+                        .saturating_add(T::DbWeight::get().reads(1_u64))
+                        .saturating_add(T::DbWeight::get().writes(1_u64))
+                }
+                fn upgrade_accounts(u: u32) -> Weight {
+                    Weight::from_parts(17_000_000, 990)
+                        .saturating_add(Weight::from_parts(13_000_000, 0).saturating_mul(u.into()))
+                        .saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(u.into())))
+                }
+            }
+
+            impl WeightInfo for () {
+                fn transfer_allow_death() -> Weight {
+                    Weight::from_parts(1, 1)
+                }
+                fn upgrade_accounts(u: u32) -> Weight {
+                    Weight::from_parts(1, 1)
+                }
+            }
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            weights["transfer_allow_death"],
+            CallWeight {
+                ref_time: 38_480_000,
+                proof_size: 3593,
+                reads: 1,
+                writes: 1
+            }
+        );
+        assert_eq!(
+            weights["upgrade_accounts"],
+            CallWeight {
+                ref_time: 17_000_000,
+                proof_size: 990,
+                reads: 0,
+                writes: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Discovers the pallet crates of a cargo workspace
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syn::Item;
+
+/// A crate of the workspace that contains a `#[frame_support::pallet]`
+/// module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalletCrate {
+    /// The package name of the crate, like `pallet-balances`
+    pub name: String,
+    /// The `Cargo.toml` of the crate
+    pub manifest_path: PathBuf,
+    /// The file that contains the pallet module
+    pub source: PathBuf,
+}
+
+/// The part of a `Cargo.toml` that names the package
+#[derive(Deserialize)]
+struct CargoToml {
+    package: Option<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+}
+
+/// Walks the directory and returns every crate that contains a
+/// `#[frame_support::pallet]` or `#[pallet]` module, sorted by name
+///
+/// Hidden directories and `target` directories are skipped.
+pub fn scan_workspace(root: impl AsRef<Path>) -> anyhow::Result<Vec<PalletCrate>> {
+    let mut manifests = Vec::new();
+    find_manifests(root.as_ref(), &mut manifests)?;
+
+    let mut crates = Vec::new();
+    for manifest_path in manifests {
+        let content = std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+        let package = match toml::from_str::<CargoToml>(&content) {
+            Ok(CargoToml {
+                package: Some(package),
+            }) => package,
+            _ => continue,
+        };
+        let src = manifest_path
+            .parent()
+            .map(|dir| dir.join("src"))
+            .unwrap_or_else(|| PathBuf::from("src"));
+        let mut sources = Vec::new();
+        find_sources(&src, &mut sources)?;
+        if let Some(source) = sources.into_iter().find(|path| is_pallet_source(path)) {
+            crates.push(PalletCrate {
+                name: package.name,
+                manifest_path,
+                source,
+            });
+        }
+    }
+    crates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(crates)
+}
+
+/// The part of the `cargo metadata` output that locates the packages
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<MetadataPackage>,
+}
+
+#[derive(Deserialize)]
+struct MetadataPackage {
+    name: String,
+    manifest_path: PathBuf,
+    targets: Vec<MetadataTarget>,
+}
+
+#[derive(Deserialize)]
+struct MetadataTarget {
+    kind: Vec<String>,
+    src_path: PathBuf,
+}
+
+/// Resolves the package with `cargo metadata` from the workspace of the
+/// current directory, or the one of `manifest_path`
+///
+/// This includes all dependencies of the workspace, so registry, path and git
+/// dependencies like `pallet-balances` are resolved to their checked out
+/// sources.
+pub fn resolve_package(name: &str, manifest_path: Option<&Path>) -> anyhow::Result<PalletCrate> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut cmd = Command::new(cargo);
+    cmd.arg("metadata").arg("--format-version=1");
+    if let Some(manifest_path) = manifest_path {
+        cmd.arg("--manifest-path").arg(manifest_path);
+    }
+    let output = cmd.output().context("Failed to run `cargo metadata`")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`cargo metadata` failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    find_package(&String::from_utf8_lossy(&output.stdout), name)
+}
+
+/// Finds the package in the `cargo metadata` output, `-` and `_` in the name
+/// are interchangeable
+fn find_package(metadata: &str, name: &str) -> anyhow::Result<PalletCrate> {
+    let metadata: Metadata =
+        serde_json::from_str(metadata).context("Failed to parse `cargo metadata`")?;
+    let normalized = |name: &str| name.replace('-', "_");
+    let package = metadata
+        .packages
+        .into_iter()
+        .find(|package| normalized(&package.name) == normalized(name))
+        .with_context(|| format!("No package `{}` in the workspace or its dependencies", name))?;
+    let lib = package
+        .targets
+        .iter()
+        .find(|target| target.kind.iter().any(|kind| kind == "lib"))
+        .with_context(|| format!("Package `{}` has no library target", package.name))?;
+
+    let mut sources = Vec::new();
+    if let Some(src) = lib.src_path.parent() {
+        find_sources(src, &mut sources)?;
+    }
+    let source = sources
+        .into_iter()
+        .find(|path| is_pallet_source(path))
+        .unwrap_or_else(|| lib.src_path.clone());
+    Ok(PalletCrate {
+        name: package.name,
+        manifest_path: package.manifest_path,
+        source,
+    })
+}
+
+/// Expands the library of the crate like `cargo expand` and returns the
+/// expanded source, which contains the generated `pallet::Call`
+///
+/// `-Zunpretty=expanded` is unstable, so `RUSTC_BOOTSTRAP` is set to make it
+/// available on stable toolchains as well.
+pub fn expand_crate(krate: &PalletCrate) -> anyhow::Result<String> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .arg("rustc")
+        .arg("--quiet")
+        .arg("--lib")
+        .arg("--profile=check")
+        .arg("--manifest-path")
+        .arg(&krate.manifest_path)
+        .arg("--")
+        .arg("-Zunpretty=expanded")
+        .env("RUSTC_BOOTSTRAP", "1")
+        .output()
+        .with_context(|| format!("Failed to expand `{}`", krate.name))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to expand `{}`:\n{}",
+            krate.name,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Whether the directory is skipped when walking the workspace
+fn is_skipped(dir: &Path) -> bool {
+    dir.file_name()
+        .map(|name| {
+            let name = name.to_string_lossy();
+            name.starts_with('.') || name == "target"
+        })
+        .unwrap_or_default()
+}
+
+fn find_manifests(dir: &Path, manifests: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            if !is_skipped(&path) {
+                find_manifests(&path, manifests)?;
+            }
+        } else if path
+            .file_name()
+            .map(|name| name == "Cargo.toml")
+            .unwrap_or_default()
+        {
+            manifests.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn find_sources(dir: &Path, sources: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    // prefer `lib.rs` over other files of the crate
+    entries.sort_by_key(|path| path.file_name().map(|name| name != "lib.rs"));
+    for path in entries {
+        if path.is_dir() {
+            find_sources(&path, sources)?;
+        } else if path.extension().map(|ext| ext == "rs").unwrap_or_default() {
+            sources.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Whether the file contains a `#[frame_support::pallet]` or `#[pallet]`
+/// module
+fn is_pallet_source(path: &Path) -> bool {
+    let content = match std::fs::read_to_string(path) {
+        // only parse files that can contain the attribute
+        Ok(content) if content.contains("pallet]") => content,
+        _ => return false,
+    };
+    match syn::parse_file(&content) {
+        Ok(file) => file.items.iter().any(is_pallet_module),
+        Err(_) => false,
+    }
+}
+
+fn is_pallet_module(item: &Item) -> bool {
+    match item {
+        Item::Mod(module) => module.attrs.iter().any(|attr| {
+            let segments = attr
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>();
+            segments == ["frame_support", "pallet"] || segments == ["pallet"]
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_scan_workspace() {
+        let root = crate::test_dir("workspace");
+        let _ = std::fs::remove_dir_all(&root);
+        for (dir, name, source) in [
+            (
+                "pallets/balances",
+                "pallet-balances",
+                "#[frame_support::pallet]\npub mod pallet {}",
+            ),
+            ("runtime", "runtime", "pub struct Runtime;"),
+        ] {
+            let dir = root.join(dir);
+            std::fs::create_dir_all(dir.join("src")).unwrap();
+            std::fs::write(
+                dir.join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n", name),
+            )
+            .unwrap();
+            std::fs::write(dir.join("src/lib.rs"), source).unwrap();
+        }
+
+        let crates = scan_workspace(&root).unwrap();
+        assert_eq!(crates.len(), 1);
+        assert_eq!(crates[0].name, "pallet-balances");
+        assert_eq!(crates[0].source, root.join("pallets/balances/src/lib.rs"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn can_find_package_in_metadata() {
+        let metadata = r#"{
+            "packages": [
+                {
+                    "name": "pallet-balances",
+                    "manifest_path": "/deps/pallet-balances/Cargo.toml",
+                    "targets": [
+                        { "kind": ["bench"], "src_path": "/deps/pallet-balances/benches/transfer.rs" },
+                        { "kind": ["lib"], "src_path": "/deps/pallet-balances/src/lib.rs" }
+                    ]
+                }
+            ]
+        }"#;
+        let krate = find_package(metadata, "pallet_balances").unwrap();
+        assert_eq!(krate.name, "pallet-balances");
+        assert_eq!(
+            krate.manifest_path,
+            PathBuf::from("/deps/pallet-balances/Cargo.toml")
+        );
+        assert_eq!(
+            krate.source,
+            PathBuf::from("/deps/pallet-balances/src/lib.rs")
+        );
+        assert!(find_package(metadata, "pallet-sudo").is_err());
+    }
+}
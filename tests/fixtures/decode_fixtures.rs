@@ -0,0 +1,36 @@
+#[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+pub enum Call {
+    #[codec(index = 2)]
+    Remark(Vec<u8>)
+}
+#[derive(Clone, PartialEq, Eq, codec::Encode, codec::Decode,)]
+pub enum RuntimeCall {
+    #[codec(index = 2)]
+    Remark(Vec<u8>)
+}
+impl From<Call> for RuntimeCall {
+    fn from(call: Call) -> Self {
+        match call {
+            Call::Remark(f0) => RuntimeCall::Remark(f0),
+        }
+    }
+}
+impl From<RuntimeCall> for Call {
+    fn from(call: RuntimeCall) -> Self {
+        match call {
+            RuntimeCall::Remark(f0) => Call::Remark(f0),
+        }
+    }
+}
+#[cfg(test)]
+mod decode_fixtures {
+    use super::*;
+    #[test]
+    fn decodes_remark() {
+        let mut input: &[u8] = &[2u8, 8u8, 1u8, 2u8];
+        let call = <RuntimeCall as codec::Decode>::decode(&mut input)
+            .expect("failed to decode `remark`");
+        assert!(input.is_empty(), "trailing bytes after the call");
+        assert!(matches!(call, RuntimeCall::Remark { .. }));
+    }
+}